@@ -0,0 +1,13 @@
+//! Rendre la scène sans fenêtre : `render_png <fichier.png> [largeur hauteur]`
+
+fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (path, width, height) = match args.as_slice() {
+        [path] => (path, rendering::DEFAULT_WIDTH, rendering::DEFAULT_HEIGHT),
+        [path, width, height] => (path, width.parse()?, height.parse()?),
+        _ => anyhow::bail!("Usage: render_png <output.png> [width height]"),
+    };
+    rendering::render_to_png(std::path::Path::new(path), width, height)?;
+    println!("Wrote {}", path);
+    Ok(())
+}
@@ -1,74 +1,330 @@
-use std::collections::HashMap;
-use anyhow::Result;
-
-#[derive(Debug, Clone)]
-pub struct BlockColor {
-    pub r: f32,
-    pub g: f32,
-    pub b: f32,
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Mutex;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::chunk_storage::{BlockId, MAX_BLOCK_ID};
+
+/// Registre embarqué, utilisé si `MINI_GAME_BLOCK_REGISTRY` n'indique pas de fichier
+const DEFAULT_REGISTRY: &str = include_str!("../blocks.json");
+
+/// Propriétés d'un bloc. Validées et gardées pour l'instant : le meshing masque toujours
+/// les faces contre un bloc voisin et les matériaux de chunk restent opaques.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockFlag {
+    /// Laisse voir les blocs derrière lui
+    Transparent,
+    /// Les entités le traversent
+    NonSolid,
+    /// Éclaire sans dépendre du soleil
+    Emissive,
 }
 
-impl BlockColor {
-    pub fn to_rgba(&self) -> [f32; 4] {
-        [self.r, self.g, self.b, 1.0]
+impl BlockFlag {
+    const NAMES: [(&'static str, BlockFlag); 3] = [
+        ("transparent", BlockFlag::Transparent),
+        ("non_solid", BlockFlag::NonSolid),
+        ("emissive", BlockFlag::Emissive),
+    ];
+
+    fn parse(name: &str) -> Result<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(known, _)| *known == name)
+            .map(|&(_, flag)| flag)
+            .with_context(|| {
+                let known: Vec<&str> = Self::NAMES.iter().map(|(known, _)| *known).collect();
+                format!("unknown flag {:?}, expected one of {}", name, known.join(", "))
+            })
     }
 }
 
-/// Gère les types de blocs et leurs couleurs
-pub struct BlockTypeManager {
-    block_key: HashMap<u32, String>,
-    block_colors: HashMap<String, BlockColor>,
+/// Définition d'un type de bloc telle qu'écrite dans le fichier
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlockEntry {
+    id: u32,
+    name: String,
+    /// Facultative pour serde, afin de signaler son absence avec le nom du bloc
+    color: Option<Vec<f32>>,
+    /// Chemins des textures, relatifs au fichier du registre
+    #[serde(default)]
+    textures: Vec<String>,
+    #[serde(default)]
+    flags: Vec<String>,
 }
 
-impl BlockTypeManager {
-    pub fn new() -> Result<Self> {
-        // Charger block_key.json
-        let block_key_json = include_str!("../block_key.json");
-        let block_key: HashMap<String, String> = serde_json::from_str(block_key_json)?;
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RegistryFile {
+    blocks: Vec<BlockEntry>,
+}
 
-        // Convertir les clés String en u32
-        let block_key: HashMap<u32, String> = block_key
-            .into_iter()
-            .filter_map(|(k, v)| k.parse::<u32>().ok().map(|id| (id, v)))
-            .collect();
+/// Type de bloc validé
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockDefinition {
+    pub id: u32,
+    pub name: String,
+    pub color: [f32; 4],
+    /// Pas encore dessinées : les chunks utilisent `color`
+    pub textures: Vec<String>,
+    pub flags: HashSet<BlockFlag>,
+}
+
+/// Types de blocs du jeu, indexés par leur ID (0 est l'air)
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockRegistry {
+    blocks: HashMap<u32, BlockDefinition>,
+    ids: HashMap<String, u32>,
+}
+
+impl BlockRegistry {
+    /// Charger `MINI_GAME_BLOCK_REGISTRY` s'il est défini, sinon le registre embarqué
+    pub fn from_env() -> Result<Self> {
+        match std::env::var_os("MINI_GAME_BLOCK_REGISTRY").filter(|path| !path.is_empty()) {
+            Some(path) => Self::load(Path::new(&path)),
+            None => Self::from_json(DEFAULT_REGISTRY).context("Invalid builtin block registry"),
+        }
+    }
 
-        // Charger block_color.json
-        let block_color_json = include_str!("../block_color.json");
-        let block_colors: HashMap<String, Vec<f32>> = serde_json::from_str(block_color_json)?;
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read block registry {}", path.display()))?;
+        Self::from_json(&json)
+            .with_context(|| format!("Invalid block registry {}", path.display()))
+    }
+
+    /// Lire et valider un registre. Toutes les erreurs sont listées dans le message.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: RegistryFile = serde_json::from_str(json)?;
+
+        let mut problems = Vec::new();
+        let mut blocks: HashMap<u32, BlockDefinition> = HashMap::with_capacity(file.blocks.len());
+        let mut ids: HashMap<String, u32> = HashMap::with_capacity(file.blocks.len());
+        for entry in file.blocks {
+            let label = format!("block {} ({:?})", entry.id, entry.name);
+            if entry.id == 0 || entry.id > MAX_BLOCK_ID as u32 {
+                problems.push(format!("{}: id must be between 1 and {}", label, MAX_BLOCK_ID));
+            }
+            if entry.name.trim().is_empty() {
+                problems.push(format!("{}: name is empty", label));
+            }
+            if let Some(other) = ids.get(&entry.name) {
+                problems.push(format!("{}: name is already used by block {}", label, other));
+            }
+            let color = parse_color(entry.color.as_deref())
+                .map_err(|e| problems.push(format!("{}: {}", label, e)));
+            if entry.textures.iter().any(|texture| texture.trim().is_empty()) {
+                problems.push(format!("{}: texture path is empty", label));
+            }
+            let mut flags = HashSet::with_capacity(entry.flags.len());
+            for flag in &entry.flags {
+                match BlockFlag::parse(flag) {
+                    Ok(flag) => {
+                        flags.insert(flag);
+                    }
+                    Err(e) => problems.push(format!("{}: {}", label, e)),
+                }
+            }
+            if let Some(other) = blocks.get(&entry.id) {
+                problems.push(format!("{}: id is already used by {:?}", label, other.name));
+                continue;
+            }
+            let Ok(color) = color else {
+                continue;
+            };
+
+            ids.entry(entry.name.clone()).or_insert(entry.id);
+            blocks.insert(
+                entry.id,
+                BlockDefinition {
+                    id: entry.id,
+                    name: entry.name,
+                    color,
+                    textures: entry.textures,
+                    flags,
+                },
+            );
+        }
 
-        // Convertir en BlockColor
-        let block_colors: HashMap<String, BlockColor> = block_colors
+        anyhow::ensure!(problems.is_empty(), "{}", problems.join("; "));
+        Ok(Self { blocks, ids })
+    }
+
+    /// Définition d'un bloc par son ID : couleur, nom, textures et drapeaux
+    pub fn get(&self, block_id: u32) -> Option<&BlockDefinition> {
+        self.blocks.get(&block_id)
+    }
+
+    /// ID d'un bloc par son nom, erreur si le registre ne le connaît pas
+    pub fn id_of(&self, name: &str) -> Result<u32> {
+        self.ids
+            .get(name)
+            .copied()
+            .with_context(|| format!("Unknown block name {:?}", name))
+    }
+
+    /// Erreur listant les IDs (hors air) absents du registre
+    pub fn ensure_registered(&self, ids: impl IntoIterator<Item = BlockId>) -> Result<()> {
+        let mut unknown: Vec<BlockId> = ids
             .into_iter()
-            .map(|(name, rgb)| {
-                (name, BlockColor {
-                    r: rgb.get(0).copied().unwrap_or(1.0),
-                    g: rgb.get(1).copied().unwrap_or(1.0),
-                    b: rgb.get(2).copied().unwrap_or(1.0),
-                })
-            })
+            .filter(|&id| id != 0 && !self.blocks.contains_key(&(id as u32)))
             .collect();
-
-        Ok(Self {
-            block_key,
-            block_colors,
-        })
+        unknown.sort_unstable();
+        unknown.dedup();
+        anyhow::ensure!(
+            unknown.is_empty(),
+            "Block ids {:?} are not in the block registry",
+            unknown
+        );
+        Ok(())
     }
 
-    /// Obtenir la couleur d'un bloc par son ID
-    pub fn get_color(&self, block_id: u32) -> Option<[f32; 4]> {
-        let block_name = self.block_key.get(&block_id)?;
-        let color = self.block_colors.get(block_name)?;
-        Some(color.to_rgba())
+    pub fn len(&self) -> usize {
+        self.blocks.len()
     }
+}
 
-    /// Obtenir le nom d'un bloc par son ID
-    pub fn get_name(&self, block_id: u32) -> Option<&str> {
-        self.block_key.get(&block_id).map(|s| s.as_str())
+/// Registre en vigueur, chargé par `from_env` au premier usage puis remplacé par Java
+static CURRENT: Mutex<Option<BlockRegistry>> = Mutex::new(None);
+
+fn with_current<T>(f: impl FnOnce(&BlockRegistry) -> Result<T>) -> Result<T> {
+    let mut current = CURRENT
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Failed to acquire lock on block registry"))?;
+    let registry = match &mut *current {
+        Some(registry) => registry,
+        None => current.insert(BlockRegistry::from_env()?),
+    };
+    f(registry)
+}
+
+/// Copie du registre en vigueur : le dernier envoyé par Java, sinon celui de `from_env`
+pub fn current() -> Result<BlockRegistry> {
+    with_current(|registry| Ok(registry.clone()))
+}
+
+/// Retenir le registre envoyé par Java, pour les noms, les IDs reçus et les prochains lancements
+pub fn set_current(registry: BlockRegistry) -> Result<()> {
+    *CURRENT
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Failed to acquire lock on block registry"))? = Some(registry);
+    Ok(())
+}
+
+/// ID du bloc `name` dans le registre en vigueur
+pub fn current_id_of(name: &str) -> Result<u32> {
+    with_current(|registry| registry.id_of(name))
+}
+
+/// Erreur si un de ces IDs (hors air) manque au registre en vigueur
+pub fn ensure_registered(ids: impl IntoIterator<Item = BlockId>) -> Result<()> {
+    with_current(|registry| registry.ensure_registered(ids))
+}
+
+/// `[r, g, b]` ou `[r, g, b, a]`, chaque composante dans [0, 1]
+fn parse_color(color: Option<&[f32]>) -> Result<[f32; 4]> {
+    let color = color.context("color is missing")?;
+    anyhow::ensure!(
+        color.iter().all(|c| (0.0..=1.0).contains(c)),
+        "color components must be between 0 and 1, got {:?}",
+        color
+    );
+    match *color {
+        [r, g, b] => Ok([r, g, b, 1.0]),
+        [r, g, b, a] => Ok([r, g, b, a]),
+        _ => anyhow::bail!("color must have 3 or 4 components, got {}", color.len()),
     }
 }
 
-impl Default for BlockTypeManager {
-    fn default() -> Self {
-        Self::new().expect("Failed to load block type configuration")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_registry_is_valid() {
+        let registry = BlockRegistry::from_json(DEFAULT_REGISTRY).unwrap();
+        let stone = registry.get(2).unwrap();
+        assert_eq!(stone.name, "stone");
+        assert_eq!(stone.color, [0.3, 0.3, 0.3, 1.0]);
+        assert!(stone.textures.is_empty() && stone.flags.is_empty());
+        assert_eq!(registry.id_of("deart").unwrap(), 1);
+        assert_eq!(registry.get(3), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn unknown_names_are_reported() {
+        let registry = BlockRegistry::from_json(DEFAULT_REGISTRY).unwrap();
+        let err = registry.id_of("dirt").unwrap_err().to_string();
+        assert_eq!(err, "Unknown block name \"dirt\"");
+    }
+
+    #[test]
+    fn every_problem_is_reported() {
+        let err = BlockRegistry::from_json(
+            r#"{ "blocks": [
+                { "id": 1, "name": "dirt", "color": [0.5, 0.3, 0.1] },
+                { "id": 1, "name": "grass" },
+                { "id": 2, "name": "stone" },
+                { "id": 3, "name": "dirt", "color": [0.5, 0.3, 0.1] },
+                { "id": 0, "name": "air", "color": [0.0, 0.0, 0.0] },
+                { "id": 4, "name": "lava", "color": [2.0, 0.5, 0.0] }
+            ] }"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("block 1 (\"grass\"): id is already used by \"dirt\""), "{}", err);
+        assert!(err.contains("block 2 (\"stone\"): color is missing"), "{}", err);
+        assert!(err.contains("block 1 (\"grass\"): color is missing"), "{}", err);
+        assert!(err.contains("block 3 (\"dirt\"): name is already used by block 1"), "{}", err);
+        assert!(err.contains("block 0 (\"air\"): id must be between"), "{}", err);
+        assert!(err.contains("block 4 (\"lava\"): color components"), "{}", err);
+    }
+
+    #[test]
+    fn textures_and_flags_are_read() {
+        let registry = BlockRegistry::from_json(
+            r#"{ "blocks": [ {
+                "id": 7, "name": "glass", "color": [0.8, 0.9, 1.0, 0.4],
+                "textures": ["glass.png", "glass_top.png"], "flags": ["transparent", "non_solid"]
+            } ] }"#,
+        )
+        .unwrap();
+        let glass = registry.get(7).unwrap();
+        assert_eq!(glass.color, [0.8, 0.9, 1.0, 0.4]);
+        assert_eq!(glass.textures, vec!["glass.png", "glass_top.png"]);
+        assert_eq!(
+            glass.flags,
+            HashSet::from([BlockFlag::Transparent, BlockFlag::NonSolid])
+        );
+    }
+
+    #[test]
+    fn bad_textures_and_flags_are_reported() {
+        let err = BlockRegistry::from_json(
+            r#"{ "blocks": [
+                { "id": 1, "name": "dirt", "color": [0.5, 0.3, 0.1], "textures": [" "] },
+                { "id": 2, "name": "gem", "color": [0.2, 0.2, 0.9], "flags": ["shiny"] }
+            ] }"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("block 1 (\"dirt\"): texture path is empty"), "{}", err);
+        assert!(err.contains("block 2 (\"gem\"): unknown flag \"shiny\""), "{}", err);
+    }
+
+    #[test]
+    fn unregistered_ids_are_reported() {
+        let registry = BlockRegistry::from_json(DEFAULT_REGISTRY).unwrap();
+        assert!(registry.ensure_registered([0, 1, 2, 1]).is_ok());
+        let err = registry.ensure_registered([9, 2, 5, 9]).unwrap_err();
+        assert_eq!(err.to_string(), "Block ids [5, 9] are not in the block registry");
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        let colour = r#"{ "blocks": [ { "id": 1, "name": "dirt", "colour": [0.5, 0.3, 0.1] } ] }"#;
+        assert!(BlockRegistry::from_json(colour).is_err());
+    }
+}
@@ -5,6 +5,16 @@ use winit::dpi::PhysicalPosition;
 use winit::event::*;
 use winit::keyboard::KeyCode;
 
+/// Qui contrôle la caméra
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraMode {
+    /// Déplacée au clavier et à la souris par `CameraController`
+    #[default]
+    FreeFly,
+    /// Position et orientation imposées par Java, les entrées ne la bougent plus
+    Driven,
+}
+
 #[derive(Debug)]
 pub struct Camera {
     position: Point3<f32>,
@@ -35,6 +45,18 @@ impl Camera {
         }
     }
 
+    /// Placer la caméra ; le tangage est borné pour ne pas passer à la verticale
+    pub fn set_pose<V: Into<Point3<f32>>, Y: Into<Rad<f32>>, P: Into<Rad<f32>>>(
+        &mut self,
+        position: V,
+        yaw: Y,
+        pitch: P,
+    ) {
+        self.position = position.into();
+        self.yaw = yaw.into();
+        self.pitch = Rad(pitch.into().0.clamp(-SAFE_FRAC_PI_2, SAFE_FRAC_PI_2));
+    }
+
     pub fn calc_matrix(&self) -> Matrix4<f32> {
         let (sin_pitch, cos_pitch) = self.pitch.0.sin_cos();
         let (sin_yaw, cos_yaw) = self.yaw.0.sin_cos();
@@ -72,6 +94,10 @@ impl Projection {
         self.aspect = width as f32 / height as f32;
     }
 
+    pub fn set_fovy<F: Into<Rad<f32>>>(&mut self, fovy: F) {
+        self.fovy = fovy.into();
+    }
+
     pub fn calc_matrix(&self) -> Matrix4<f32> {
         OPENGL_TO_WGPU_MATRIX * perspective(self.fovy, self.aspect, self.znear, self.zfar)
     }
@@ -176,6 +202,14 @@ impl CameraController {
         self.rotate_vertical = mouse_dy as f32;
     }
 
+    /// Oublier la souris et la molette reçues depuis la dernière mise à jour, pour que la
+    /// caméra ne saute pas en repassant en `FreeFly`
+    pub fn clear_pending_motion(&mut self) {
+        self.rotate_horizontal = 0.0;
+        self.rotate_vertical = 0.0;
+        self.scroll = 0.0;
+    }
+
     pub fn handle_mouse_scroll(&mut self, delta: &MouseScrollDelta) {
         self.scroll = -match delta {
             // I'm assuming a line is about 100 pixels
@@ -227,4 +261,23 @@ impl CameraController {
             camera.pitch = Rad(SAFE_FRAC_PI_2);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleared_motion_does_not_move_the_camera() {
+        let mut controller = CameraController::new(4.0, 0.4);
+        let mut camera = Camera::new((0.0, 0.0, 0.0), Rad(0.0), Rad(0.0));
+        controller.handle_mouse(500.0, -300.0, false);
+        controller.handle_mouse_scroll(&MouseScrollDelta::LineDelta(0.0, 3.0));
+
+        controller.clear_pending_motion();
+        controller.update_camera(&mut camera, Duration::from_secs(1));
+        assert_eq!(camera.yaw, Rad(0.0));
+        assert_eq!(camera.pitch, Rad(0.0));
+        assert_eq!(camera.position, Point3::new(0.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,646 @@
+use crate::chunk_storage::ChunkStorage;
+use crate::render_backend::Vertex;
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// Taille d'un chunk sur chaque axe
+pub const CHUNK_SIZE: usize = 32;
+
+/// Convertir les coordonnées 3D en index 1D (même ordre que `Chunk.java`)
+pub fn coord_to_index(x: usize, y: usize, z: usize) -> usize {
+    y * (CHUNK_SIZE * CHUNK_SIZE) + z * CHUNK_SIZE + x
+}
+
+/// Une des six faces d'un bloc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Face {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl Face {
+    pub const ALL: [Face; 6] = [
+        Face::PosX,
+        Face::NegX,
+        Face::PosY,
+        Face::NegY,
+        Face::PosZ,
+        Face::NegZ,
+    ];
+
+    /// Axe perpendiculaire à la face (0 = x, 1 = y, 2 = z)
+    pub fn axis(self) -> usize {
+        match self {
+            Face::PosX | Face::NegX => 0,
+            Face::PosY | Face::NegY => 1,
+            Face::PosZ | Face::NegZ => 2,
+        }
+    }
+
+    pub fn is_positive(self) -> bool {
+        matches!(self, Face::PosX | Face::PosY | Face::PosZ)
+    }
+
+    /// Direction vers le bloc voisin qui cache cette face
+    pub fn offset(self) -> [i32; 3] {
+        let mut offset = [0; 3];
+        offset[self.axis()] = if self.is_positive() { 1 } else { -1 };
+        offset
+    }
+
+    /// Position de la face dans `Face::ALL`
+    pub fn index(self) -> usize {
+        match self {
+            Face::PosX => 0,
+            Face::NegX => 1,
+            Face::PosY => 2,
+            Face::NegY => 3,
+            Face::PosZ => 4,
+            Face::NegZ => 5,
+        }
+    }
+
+    /// Normale sortante de la face
+    pub fn normal(self) -> [f32; 3] {
+        self.offset().map(|c| c as f32)
+    }
+}
+
+/// Rectangle de faces visibles, en coordonnées de blocs du chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quad {
+    pub face: Face,
+    pub block_id: u32,
+    /// Position de la couche le long de l'axe de la face
+    pub layer: usize,
+    /// Coin du rectangle sur les deux autres axes (u, v)
+    pub start: [usize; 2],
+    /// Taille du rectangle sur (u, v), 1x1 sans fusion
+    pub size: [usize; 2],
+}
+
+impl Quad {
+    pub fn normal(&self) -> [f32; 3] {
+        self.face.normal()
+    }
+
+    /// Ajouter les 4 sommets et 6 indices du quad
+    fn emit(&self, vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+        let d = self.face.axis();
+        let u = (d + 1) % 3;
+        let v = (d + 2) % 3;
+
+        // Le plan d'une face positive est sur le bord "haut" du bloc
+        let plane = if self.face.is_positive() { self.layer + 1 } else { self.layer };
+        let [w, h] = self.size;
+
+        let corner = |du: usize, dv: usize| {
+            let mut p = [0.0f32; 3];
+            p[d] = plane as f32;
+            p[u] = (self.start[0] + du) as f32;
+            p[v] = (self.start[1] + dv) as f32;
+            // Les blocs sont centrés sur leurs coordonnées entières
+            p.map(|c| c - 0.5)
+        };
+
+        let base = vertices.len() as u32;
+        let normal = self.normal();
+        let vertex = |position, tex_coords| Vertex {
+            position,
+            tex_coords,
+            normal,
+            ..Default::default()
+        };
+        let corners = [
+            vertex(corner(0, 0), [0.0, h as f32]),
+            vertex(corner(w, 0), [w as f32, h as f32]),
+            vertex(corner(w, h), [w as f32, 0.0]),
+            vertex(corner(0, h), [0.0, 0.0]),
+        ];
+        vertices.extend_from_slice(&corners);
+
+        // (u, v, d) forme un repère direct : le sens anti-horaire regarde vers +d
+        if self.face.is_positive() {
+            indices.extend_from_slice(&[base, base + 1, base + 2, base + 2, base + 3, base]);
+        } else {
+            indices.extend_from_slice(&[base, base + 3, base + 2, base + 2, base + 1, base]);
+        }
+    }
+}
+
+/// Plage d'indices partageant le même type de bloc
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockGroup {
+    pub block_id: u32,
+    pub indices: Range<u32>,
+}
+
+/// Géométrie CPU d'un chunk : un seul buffer de sommets/indices, découpé par type de bloc
+#[derive(Debug, Default)]
+pub struct ChunkMeshData {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub groups: Vec<BlockGroup>,
+}
+
+impl ChunkMeshData {
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Nombre de quads (faces fusionnées) du mesh
+    pub fn quad_count(&self) -> usize {
+        self.indices.len() / 6
+    }
+}
+
+fn read_block(chunk: &ChunkStorage, x: usize, y: usize, z: usize) -> u32 {
+    chunk.get(x, y, z) as u32
+}
+
+/// Blocs d'un chunk et de ses six voisins, pour cacher les faces sur les bords
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkNeighbourhood<'a> {
+    pub center: &'a ChunkStorage,
+    /// Chunks voisins chargés, indexés par `Face::index`
+    pub neighbours: [Option<&'a ChunkStorage>; 6],
+}
+
+impl<'a> ChunkNeighbourhood<'a> {
+    /// Chunk isolé : tout ce qui est hors du chunk est de l'air
+    pub fn isolated(center: &'a ChunkStorage) -> Self {
+        Self {
+            center,
+            neighbours: [None; 6],
+        }
+    }
+
+    /// Aucune face visible : chunk vide, ou plein et entouré de voisins pleins
+    fn is_hidden(&self) -> bool {
+        match self.center.uniform_block() {
+            Some(0) => true,
+            Some(_) => self.neighbours.iter().all(|neighbour| {
+                neighbour
+                    .and_then(ChunkStorage::uniform_block)
+                    .is_some_and(|block| block != 0)
+            }),
+            None => false,
+        }
+    }
+
+    /// Type du bloc en coordonnées locales, qui peuvent déborder d'un bloc sur un voisin
+    fn block_at(&self, x: i32, y: i32, z: i32) -> u32 {
+        let size = CHUNK_SIZE as i32;
+        let pos = [x, y, z];
+
+        let mut outside = None;
+        for (axis, &c) in pos.iter().enumerate() {
+            if c < 0 || c >= size {
+                if outside.is_some() {
+                    return 0;
+                }
+                outside = Some(axis);
+            }
+        }
+
+        let Some(axis) = outside else {
+            return read_block(self.center, x as usize, y as usize, z as usize);
+        };
+
+        let face = match (axis, pos[axis] < 0) {
+            (0, false) => Face::PosX,
+            (0, true) => Face::NegX,
+            (1, false) => Face::PosY,
+            (1, true) => Face::NegY,
+            (2, false) => Face::PosZ,
+            _ => Face::NegZ,
+        };
+        let Some(neighbour) = self.neighbours[face.index()] else {
+            return 0;
+        };
+
+        // Ramener la coordonnée dans le voisin (un seul bloc de débordement)
+        let local = pos.map(|c| c.rem_euclid(size) as usize);
+        read_block(neighbour, local[0], local[1], local[2])
+    }
+}
+
+/// Calculer le masque d'une couche : type du bloc si sa face est visible, 0 sinon
+fn build_mask(chunk: &ChunkNeighbourhood, face: Face, layer: usize, mask: &mut [u32]) {
+    let d = face.axis();
+    let u = (d + 1) % 3;
+    let v = (d + 2) % 3;
+    let offset = face.offset();
+
+    for j in 0..CHUNK_SIZE {
+        for i in 0..CHUNK_SIZE {
+            let mut pos = [0i32; 3];
+            pos[d] = layer as i32;
+            pos[u] = i as i32;
+            pos[v] = j as i32;
+
+            let block = chunk.block_at(pos[0], pos[1], pos[2]);
+            let neighbour = chunk.block_at(
+                pos[0] + offset[0],
+                pos[1] + offset[1],
+                pos[2] + offset[2],
+            );
+
+            mask[j * CHUNK_SIZE + i] = if block != 0 && neighbour == 0 { block } else { 0 };
+        }
+    }
+}
+
+/// Fusionner le masque en rectangles maximaux de même type
+fn merge_mask(face: Face, layer: usize, mask: &mut [u32], quads: &mut Vec<Quad>) {
+    for j in 0..CHUNK_SIZE {
+        let mut i = 0;
+        while i < CHUNK_SIZE {
+            let block_id = mask[j * CHUNK_SIZE + i];
+            if block_id == 0 {
+                i += 1;
+                continue;
+            }
+
+            // Étendre sur u tant que le type est identique
+            let mut w = 1;
+            while i + w < CHUNK_SIZE && mask[j * CHUNK_SIZE + i + w] == block_id {
+                w += 1;
+            }
+
+            // Étendre sur v tant que toute la ligne correspond
+            let mut h = 1;
+            'grow: while j + h < CHUNK_SIZE {
+                for k in 0..w {
+                    if mask[(j + h) * CHUNK_SIZE + i + k] != block_id {
+                        break 'grow;
+                    }
+                }
+                h += 1;
+            }
+
+            for dv in 0..h {
+                for du in 0..w {
+                    mask[(j + dv) * CHUNK_SIZE + i + du] = 0;
+                }
+            }
+
+            quads.push(Quad {
+                face,
+                block_id,
+                layer,
+                start: [i, j],
+                size: [w, h],
+            });
+            i += w;
+        }
+    }
+}
+
+/// Une face visible par bloc, sans fusion
+fn collect_mask(face: Face, layer: usize, mask: &[u32], quads: &mut Vec<Quad>) {
+    for j in 0..CHUNK_SIZE {
+        for i in 0..CHUNK_SIZE {
+            let block_id = mask[j * CHUNK_SIZE + i];
+            if block_id != 0 {
+                quads.push(Quad {
+                    face,
+                    block_id,
+                    layer,
+                    start: [i, j],
+                    size: [1, 1],
+                });
+            }
+        }
+    }
+}
+
+/// Stratégie de construction du maillage d'un chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MeshingMode {
+    /// Une face par bloc visible (référence pour comparer les performances)
+    Culled,
+    /// Faces visibles fusionnées en rectangles maximaux
+    #[default]
+    Greedy,
+}
+
+impl MeshingMode {
+    /// Lire le mode depuis `MINI_GAME_MESHING` (`culled` ou `greedy`)
+    pub fn from_env() -> Self {
+        match std::env::var("MINI_GAME_MESHING").as_deref() {
+            Ok("culled") => MeshingMode::Culled,
+            _ => MeshingMode::Greedy,
+        }
+    }
+}
+
+/// Maillage CPU d'un chunk : la liste des faces visibles, testable sans device
+#[derive(Debug, Default, Clone)]
+pub struct ChunkMesh {
+    quads: Vec<Quad>,
+}
+
+impl ChunkMesh {
+    /// Construire le maillage en tenant compte des chunks voisins chargés
+    pub fn build_with_neighbours(chunk: &ChunkNeighbourhood, mode: MeshingMode) -> Self {
+        let mut quads = Vec::new();
+        if chunk.is_hidden() {
+            return Self { quads };
+        }
+
+        let mut mask = vec![0u32; CHUNK_SIZE * CHUNK_SIZE];
+
+        for face in Face::ALL {
+            for layer in 0..CHUNK_SIZE {
+                build_mask(chunk, face, layer, &mut mask);
+                match mode {
+                    MeshingMode::Culled => collect_mask(face, layer, &mask, &mut quads),
+                    MeshingMode::Greedy => merge_mask(face, layer, &mut mask, &mut quads),
+                }
+            }
+        }
+
+        Self { quads }
+    }
+
+    /// Générer les sommets/indices, regroupés par type de bloc
+    pub fn to_mesh_data(&self) -> ChunkMeshData {
+        // Un seul matériau par plage d'indices
+        let mut by_block: BTreeMap<u32, Vec<&Quad>> = BTreeMap::new();
+        for quad in &self.quads {
+            by_block.entry(quad.block_id).or_default().push(quad);
+        }
+
+        let mut mesh = ChunkMeshData::default();
+        for (block_id, quads) in by_block {
+            let start = mesh.indices.len() as u32;
+            for quad in quads {
+                quad.emit(&mut mesh.vertices, &mut mesh.indices);
+            }
+            mesh.groups.push(BlockGroup {
+                block_id,
+                indices: start..mesh.indices.len() as u32,
+            });
+        }
+
+        mesh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_chunk() -> ChunkStorage {
+        ChunkStorage::empty()
+    }
+
+    /// Mesh d'un chunk isolé, construit comme par les threads de meshing
+    fn mesh(chunk: &ChunkStorage, mode: MeshingMode) -> ChunkMeshData {
+        mesh_with(&ChunkNeighbourhood::isolated(chunk), mode)
+    }
+
+    fn mesh_with(neighbourhood: &ChunkNeighbourhood, mode: MeshingMode) -> ChunkMeshData {
+        ChunkMesh::build_with_neighbours(neighbourhood, mode).to_mesh_data()
+    }
+
+    fn greedy_mesh(chunk: &ChunkStorage) -> ChunkMeshData {
+        mesh(chunk, MeshingMode::Greedy)
+    }
+
+    fn culled_mesh(chunk: &ChunkStorage) -> ChunkMeshData {
+        mesh(chunk, MeshingMode::Culled)
+    }
+
+    /// Nombre de quads tournés vers `face` (4 sommets par quad, de même normale)
+    fn quads_facing(mesh: &ChunkMeshData, face: Face) -> usize {
+        mesh.vertices
+            .chunks(4)
+            .filter(|quad| quad[0].normal == face.normal())
+            .count()
+    }
+
+    /// Nombre de quads d'un type de bloc, d'après sa plage d'indices
+    fn block_quads(mesh: &ChunkMeshData, block_id: u32) -> usize {
+        mesh.groups
+            .iter()
+            .filter(|group| group.block_id == block_id)
+            .map(|group| group.indices.len() / 6)
+            .sum()
+    }
+
+    #[test]
+    fn empty_chunk_has_no_geometry() {
+        let mesh = greedy_mesh(&empty_chunk());
+        assert!(mesh.is_empty());
+        assert!(mesh.groups.is_empty());
+    }
+
+    #[test]
+    fn buried_uniform_chunk_is_skipped() {
+        let stone = ChunkStorage::Uniform(2);
+        let mut neighbourhood = ChunkNeighbourhood::isolated(&stone);
+        neighbourhood.neighbours = [Some(&stone); 6];
+        assert!(mesh_with(&neighbourhood, MeshingMode::Greedy).is_empty());
+
+        // Un seul voisin manquant expose la face de ce côté
+        neighbourhood.neighbours[Face::PosY.index()] = None;
+        let mesh = mesh_with(&neighbourhood, MeshingMode::Greedy);
+        assert_eq!(mesh.quad_count(), 1);
+        assert_eq!(quads_facing(&mesh, Face::PosY), 1);
+    }
+
+    #[test]
+    fn single_block_yields_six_quads() {
+        let mut chunk = empty_chunk();
+        chunk.set(3, 4, 5, 2);
+
+        let mesh = greedy_mesh(&chunk);
+        assert_eq!(mesh.quad_count(), 6);
+        assert_eq!(mesh.vertices.len(), 24);
+        assert_eq!(mesh.groups, vec![BlockGroup { block_id: 2, indices: 0..36 }]);
+
+        for vertex in &mesh.vertices {
+            for (c, center) in vertex.position.iter().zip([3.0, 4.0, 5.0]) {
+                assert_eq!((c - center).abs(), 0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn flat_floor_merges_into_six_quads() {
+        let mut chunk = empty_chunk();
+        for x in 0..10 {
+            for z in 0..10 {
+                chunk.set(x, 0, z, 2);
+            }
+        }
+
+        let mesh = greedy_mesh(&chunk);
+        assert_eq!(mesh.quad_count(), 6);
+    }
+
+    #[test]
+    fn different_types_are_not_merged() {
+        let mut chunk = empty_chunk();
+        chunk.set(0, 0, 0, 1);
+        chunk.set(1, 0, 0, 2);
+
+        let mesh = greedy_mesh(&chunk);
+        // Chaque bloc garde 5 faces, la face commune est cachée
+        assert_eq!(mesh.quad_count(), 10);
+        assert_eq!(mesh.groups.len(), 2);
+        assert_eq!(mesh.groups[0].block_id, 1);
+        assert_eq!(mesh.groups[1].block_id, 2);
+    }
+
+    #[test]
+    fn solid_chunk_only_meshes_its_surface() {
+        let chunk = ChunkStorage::Uniform(2);
+        let mesh = greedy_mesh(&chunk);
+        assert_eq!(mesh.quad_count(), 6);
+    }
+
+    #[test]
+    fn faces_are_wound_counter_clockwise_from_outside() {
+        let mut chunk = empty_chunk();
+        chunk.set(0, 0, 0, 1);
+
+        let mesh = greedy_mesh(&chunk);
+        for triangle in mesh.indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|k| mesh.vertices[triangle[k] as usize].position);
+            let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+            let ac = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+            let normal = [
+                ab[1] * ac[2] - ab[2] * ac[1],
+                ab[2] * ac[0] - ab[0] * ac[2],
+                ab[0] * ac[1] - ab[1] * ac[0],
+            ];
+            // La normale doit pointer hors du bloc centré en (0, 0, 0)
+            let center = [0, 1, 2].map(|k| (a[k] + b[k] + c[k]) / 3.0);
+            let dot: f32 = (0..3).map(|k| normal[k] * center[k]).sum();
+            assert!(dot > 0.0);
+
+            // Et la normale des sommets suit le même sens que l'enroulement
+            let stored = mesh.vertices[triangle[0] as usize].normal;
+            let agreement: f32 = (0..3).map(|k| normal[k] * stored[k]).sum();
+            assert!(agreement > 0.0);
+        }
+    }
+
+    #[test]
+    fn culled_single_block_yields_six_quads() {
+        let mut chunk = empty_chunk();
+        chunk.set(7, 7, 7, 1);
+
+        let mesh = culled_mesh(&chunk);
+        assert_eq!(mesh.quad_count(), 6);
+        for face in Face::ALL {
+            assert_eq!(quads_facing(&mesh, face), 1);
+        }
+        assert_eq!(block_quads(&mesh, 1), 6);
+        // Quads de 1x1 : les coordonnées de texture suivent la taille du quad
+        assert!(mesh.vertices.iter().all(|v| v.tex_coords.iter().all(|&t| t <= 1.0)));
+    }
+
+    #[test]
+    fn culled_adjacent_blocks_yield_ten_quads() {
+        let mut chunk = empty_chunk();
+        chunk.set(0, 0, 0, 1);
+        chunk.set(1, 0, 0, 1);
+
+        let mesh = culled_mesh(&chunk);
+        assert_eq!(mesh.quad_count(), 10);
+        // La face commune sur l'axe x est cachée des deux côtés
+        assert_eq!(quads_facing(&mesh, Face::PosX), 1);
+        assert_eq!(quads_facing(&mesh, Face::NegX), 1);
+        assert_eq!(quads_facing(&mesh, Face::PosY), 2);
+
+        // Le greedy meshing fusionne les mêmes faces en 6 quads
+        assert_eq!(greedy_mesh(&chunk).quad_count(), 6);
+    }
+
+    #[test]
+    fn culled_counts_faces_per_block_type() {
+        let mut chunk = empty_chunk();
+        chunk.set(0, 0, 0, 1);
+        chunk.set(0, 1, 0, 2);
+
+        let mesh = culled_mesh(&chunk);
+        assert_eq!(block_quads(&mesh, 1), 5);
+        assert_eq!(block_quads(&mesh, 2), 5);
+        assert_eq!(block_quads(&mesh, 3), 0);
+    }
+
+    #[test]
+    fn quad_normals_match_their_face() {
+        let mut chunk = empty_chunk();
+        chunk.set(4, 4, 4, 1);
+
+        let mesh = culled_mesh(&chunk);
+        let normals: Vec<[f32; 3]> = mesh.vertices.iter().map(|v| v.normal).collect();
+        for expected in [
+            [1.0, 0.0, 0.0],
+            [-1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, -1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [0.0, 0.0, -1.0],
+        ] {
+            assert!(normals.contains(&expected));
+        }
+    }
+
+    #[test]
+    fn culled_floor_emits_every_exposed_face() {
+        let mut chunk = empty_chunk();
+        for x in 0..10 {
+            for z in 0..10 {
+                chunk.set(x, 0, z, 2);
+            }
+        }
+
+        let mesh = culled_mesh(&chunk);
+        // Dessus + dessous + 4 bords de 10 faces
+        assert_eq!(mesh.quad_count(), 100 + 100 + 40);
+        assert_eq!(mesh.vertices.len(), 240 * 4);
+    }
+
+    #[test]
+    fn border_faces_are_hidden_by_loaded_neighbour() {
+        let mut chunk = empty_chunk();
+        chunk.set(CHUNK_SIZE - 1, 0, 0, 1);
+        let mut east = empty_chunk();
+        east.set(0, 0, 0, 1);
+
+        let mut neighbourhood = ChunkNeighbourhood::isolated(&chunk);
+        assert_eq!(quads_facing(&mesh_with(&neighbourhood, MeshingMode::Culled), Face::PosX), 1);
+
+        neighbourhood.neighbours[Face::PosX.index()] = Some(&east);
+        let mesh = mesh_with(&neighbourhood, MeshingMode::Culled);
+        assert_eq!(quads_facing(&mesh, Face::PosX), 0);
+        assert_eq!(mesh.quad_count(), 5);
+    }
+
+    #[test]
+    fn neighbour_only_affects_its_own_side() {
+        let mut chunk = empty_chunk();
+        chunk.set(0, 0, 0, 1);
+        let full = ChunkStorage::Uniform(1);
+
+        let mut neighbourhood = ChunkNeighbourhood::isolated(&chunk);
+        neighbourhood.neighbours[Face::NegY.index()] = Some(&full);
+        let mesh = mesh_with(&neighbourhood, MeshingMode::Culled);
+
+        // Seule la face du bas touche le voisin du dessous
+        assert_eq!(quads_facing(&mesh, Face::NegY), 0);
+        assert_eq!(quads_facing(&mesh, Face::NegX), 1);
+        assert_eq!(quads_facing(&mesh, Face::NegZ), 1);
+        assert_eq!(mesh.quad_count(), 5);
+    }
+}
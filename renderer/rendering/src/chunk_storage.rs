@@ -0,0 +1,396 @@
+use crate::chunk_mesher::{coord_to_index, CHUNK_SIZE};
+use std::collections::HashMap;
+
+/// Identifiant d'un type de bloc (0 = air)
+pub type BlockId = u16;
+
+/// Nombre de blocs dans un chunk
+pub const CHUNK_VOLUME: usize = CHUNK_SIZE * CHUNK_SIZE * CHUNK_SIZE;
+
+/// Plus grand identifiant accepté. Le bit de poids fort est réservé : côté Java les ids
+/// passent par des `short` signés, où 32768..=65535 deviendraient négatifs.
+pub const MAX_BLOCK_ID: BlockId = i16::MAX as BlockId;
+
+/// Blocs d'un chunk : une palette locale et des indices compactés sur quelques bits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkStorage {
+    /// Tout le chunk est du même bloc (cas courant : air ou pierre)
+    Uniform(BlockId),
+    Paletted(PalettedBlocks),
+}
+
+/// Indices de palette rangés dans des mots de 64 bits, sans chevauchement entre deux mots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PalettedBlocks {
+    palette: Vec<BlockId>,
+    /// Nombre de blocs de chaque entrée de la palette ; une entrée à 0 est libre
+    counts: Vec<u32>,
+    bits: u32,
+    words: Vec<u64>,
+}
+
+impl PalettedBlocks {
+    /// Palette et indices déjà calculés, `counts` comptant les blocs de chaque entrée
+    fn from_indices(
+        palette: Vec<BlockId>,
+        counts: Vec<u32>,
+        indices: impl Iterator<Item = usize>,
+    ) -> Self {
+        let bits = bits_for(palette.len());
+        let mut blocks = Self {
+            palette,
+            counts,
+            bits,
+            words: vec![0; CHUNK_VOLUME.div_ceil(per_word(bits))],
+        };
+        for (idx, value) in indices.enumerate() {
+            blocks.set_index(idx, value);
+        }
+        blocks
+    }
+
+    fn filled(block: BlockId) -> Self {
+        Self::from_indices(vec![block], vec![CHUNK_VOLUME as u32], std::iter::empty())
+    }
+
+    fn index_at(&self, idx: usize) -> usize {
+        let per_word = per_word(self.bits);
+        let shift = (idx % per_word) as u32 * self.bits;
+        let mask = (1u64 << self.bits) - 1;
+        ((self.words[idx / per_word] >> shift) & mask) as usize
+    }
+
+    fn set_index(&mut self, idx: usize, value: usize) {
+        let per_word = per_word(self.bits);
+        let shift = (idx % per_word) as u32 * self.bits;
+        let mask = (1u64 << self.bits) - 1;
+        let word = &mut self.words[idx / per_word];
+        *word = (*word & !(mask << shift)) | ((value as u64) << shift);
+    }
+
+    fn get(&self, idx: usize) -> BlockId {
+        self.palette[self.index_at(idx)]
+    }
+
+    /// Index de palette du bloc : son entrée, sinon une entrée libre, sinon une nouvelle
+    /// (en élargissant les indices si besoin)
+    fn palette_index(&mut self, block: BlockId) -> usize {
+        if let Some(i) = self.palette.iter().position(|&b| b == block) {
+            return i;
+        }
+        if let Some(i) = self.counts.iter().position(|&count| count == 0) {
+            self.palette[i] = block;
+            return i;
+        }
+
+        self.palette.push(block);
+        self.counts.push(0);
+        if bits_for(self.palette.len()) != self.bits {
+            self.repack((0..self.palette.len()).collect());
+        }
+        self.palette.len() - 1
+    }
+
+    fn set(&mut self, idx: usize, block: BlockId) {
+        let old = self.index_at(idx);
+        self.counts[old] -= 1;
+        let value = self.palette_index(block);
+        self.counts[value] += 1;
+        self.set_index(idx, value);
+    }
+
+    /// Entrées de la palette encore utilisées
+    fn used(&self) -> usize {
+        self.counts.iter().filter(|&&count| count > 0).count()
+    }
+
+    /// Retirer les entrées libres si les indices peuvent alors tenir sur moins de bits
+    fn shrink(&mut self) {
+        if bits_for(self.used()) >= self.bits {
+            return;
+        }
+        let kept: Vec<usize> = (0..self.palette.len()).filter(|&i| self.counts[i] > 0).collect();
+        self.repack(kept);
+    }
+
+    /// Réécrire les indices pour la palette faite des entrées `kept`, dans cet ordre
+    fn repack(&mut self, kept: Vec<usize>) {
+        let mut remap = vec![0; self.palette.len()];
+        for (new, &old) in kept.iter().enumerate() {
+            remap[old] = new;
+        }
+        let palette = kept.iter().map(|&i| self.palette[i]).collect();
+        let counts = kept.iter().map(|&i| self.counts[i]).collect();
+        let indices = (0..CHUNK_VOLUME).map(|idx| remap[self.index_at(idx)]);
+        *self = Self::from_indices(palette, counts, indices);
+    }
+}
+
+/// Bits nécessaires pour indexer une palette de `len` entrées (au moins 1)
+fn bits_for(len: usize) -> u32 {
+    (usize::BITS - len.saturating_sub(1).leading_zeros()).max(1)
+}
+
+fn per_word(bits: u32) -> usize {
+    (64 / bits) as usize
+}
+
+impl ChunkStorage {
+    /// Chunk vide (uniquement de l'air)
+    pub fn empty() -> Self {
+        ChunkStorage::Uniform(0)
+    }
+
+    /// Construire à partir d'un identifiant par bloc, dans l'ordre de `coord_to_index`
+    pub fn from_ids(ids: &[BlockId]) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            ids.len() == CHUNK_VOLUME,
+            "Chunk has {} blocks, expected {}",
+            ids.len(),
+            CHUNK_VOLUME
+        );
+        if let Some(idx) = ids.iter().position(|&id| id > MAX_BLOCK_ID) {
+            anyhow::bail!(
+                "Invalid block id {} at index {}, max is {}",
+                ids[idx],
+                idx,
+                MAX_BLOCK_ID
+            );
+        }
+
+        let first = ids[0];
+        if ids.iter().all(|&id| id == first) {
+            return Ok(ChunkStorage::Uniform(first));
+        }
+
+        // Palette construite dans l'ordre d'apparition
+        let mut palette = Vec::new();
+        let mut counts: Vec<u32> = Vec::new();
+        let mut lookup: HashMap<BlockId, usize> = HashMap::new();
+        let mut indices = Vec::with_capacity(CHUNK_VOLUME);
+        for &id in ids {
+            let value = *lookup.entry(id).or_insert_with(|| {
+                palette.push(id);
+                counts.push(0);
+                palette.len() - 1
+            });
+            counts[value] += 1;
+            indices.push(value);
+        }
+
+        let blocks = PalettedBlocks::from_indices(palette, counts, indices.into_iter());
+        Ok(ChunkStorage::Paletted(blocks))
+    }
+
+    /// Convertir l'ancien format `float[]` envoyé par Java (un type de bloc par float)
+    pub fn from_legacy(data: &[f32]) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            data.len() == CHUNK_VOLUME,
+            "Chunk has {} blocks, expected {}",
+            data.len(),
+            CHUNK_VOLUME
+        );
+
+        let mut ids = Vec::with_capacity(CHUNK_VOLUME);
+        for (idx, &value) in data.iter().enumerate() {
+            anyhow::ensure!(
+                value.fract() == 0.0 && (0.0..=BlockId::MAX as f32).contains(&value),
+                "Invalid block id {} at index {}",
+                value,
+                idx
+            );
+            ids.push(value as BlockId);
+        }
+        Self::from_ids(&ids)
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> BlockId {
+        self.get_index(coord_to_index(x, y, z))
+    }
+
+    pub fn get_index(&self, idx: usize) -> BlockId {
+        match self {
+            ChunkStorage::Uniform(block) => *block,
+            ChunkStorage::Paletted(blocks) => blocks.get(idx),
+        }
+    }
+
+    /// Modifier un bloc, renvoie false si le bloc avait déjà ce type
+    pub fn set(&mut self, x: usize, y: usize, z: usize, block: BlockId) -> bool {
+        let idx = coord_to_index(x, y, z);
+        if self.get_index(idx) == block {
+            return false;
+        }
+
+        if let ChunkStorage::Uniform(fill) = *self {
+            *self = ChunkStorage::Paletted(PalettedBlocks::filled(fill));
+        }
+        if let ChunkStorage::Paletted(blocks) = self {
+            blocks.set(idx, block);
+            // Un bloc effacé peut libérer une entrée de la palette, voire tout le chunk
+            if blocks.used() == 1 {
+                *self = ChunkStorage::Uniform(block);
+            } else {
+                blocks.shrink();
+            }
+        }
+        true
+    }
+
+    /// Types de blocs présents dans le chunk, chacun une fois
+    pub fn block_ids(&self) -> Vec<BlockId> {
+        match self {
+            ChunkStorage::Uniform(block) => vec![*block],
+            ChunkStorage::Paletted(blocks) => blocks
+                .palette
+                .iter()
+                .zip(&blocks.counts)
+                .filter(|&(_, &count)| count > 0)
+                .map(|(&block, _)| block)
+                .collect(),
+        }
+    }
+
+    /// Bloc unique si le chunk est uniforme
+    pub fn uniform_block(&self) -> Option<BlockId> {
+        match self {
+            ChunkStorage::Uniform(block) => Some(*block),
+            ChunkStorage::Paletted(_) => None,
+        }
+    }
+
+}
+
+impl Default for ChunkStorage {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Nombre de bits par bloc (0 pour un chunk uniforme)
+    fn bits_per_block(storage: &ChunkStorage) -> u32 {
+        match storage {
+            ChunkStorage::Uniform(_) => 0,
+            ChunkStorage::Paletted(blocks) => blocks.bits,
+        }
+    }
+
+    #[test]
+    fn bits_grow_with_palette() {
+        assert_eq!(bits_for(1), 1);
+        assert_eq!(bits_for(2), 1);
+        assert_eq!(bits_for(3), 2);
+        assert_eq!(bits_for(16), 4);
+        assert_eq!(bits_for(17), 5);
+    }
+
+    #[test]
+    fn uniform_chunks_use_no_index_storage() {
+        let storage = ChunkStorage::from_ids(&vec![7; CHUNK_VOLUME]).unwrap();
+        assert_eq!(storage, ChunkStorage::Uniform(7));
+        assert_eq!(storage.get(31, 31, 31), 7);
+        assert_eq!(bits_per_block(&storage), 0);
+    }
+
+    #[test]
+    fn set_leaves_uniform_and_widens_indices() {
+        let mut storage = ChunkStorage::empty();
+        assert!(storage.set(1, 2, 3, 5));
+        assert!(!storage.set(1, 2, 3, 5));
+        assert_eq!(bits_per_block(&storage), 1);
+
+        for id in 1..=20 {
+            storage.set(id as usize, 0, 0, id);
+        }
+        assert_eq!(bits_per_block(&storage), 5);
+        assert_eq!(storage.get(1, 2, 3), 5);
+        for id in 1..=20 {
+            assert_eq!(storage.get(id as usize, 0, 0), id);
+        }
+        assert_eq!(storage.get(0, 0, 0), 0);
+        assert_eq!(storage.get(31, 31, 31), 0);
+    }
+
+    #[test]
+    fn cleared_blocks_free_their_palette_entries() {
+        let mut storage = ChunkStorage::empty();
+        for id in 1..=20 {
+            storage.set(id as usize, 0, 0, id);
+        }
+        assert_eq!(bits_per_block(&storage), 5);
+
+        // Les entrées libérées sont réutilisées au lieu d'agrandir la palette
+        storage.set(1, 0, 0, 0);
+        storage.set(1, 0, 0, 21);
+        assert_eq!(bits_per_block(&storage), 5);
+
+        for x in 3..=20 {
+            storage.set(x, 0, 0, 0);
+        }
+        assert_eq!(bits_per_block(&storage), 2);
+        assert_eq!(storage.get(1, 0, 0), 21);
+        assert_eq!(storage.get(2, 0, 0), 2);
+        assert_eq!(storage.get(3, 0, 0), 0);
+
+        storage.set(1, 0, 0, 0);
+        storage.set(2, 0, 0, 0);
+        assert_eq!(storage, ChunkStorage::Uniform(0));
+    }
+
+    #[test]
+    fn block_ids_skip_freed_entries() {
+        let mut storage = ChunkStorage::Uniform(2);
+        assert_eq!(storage.block_ids(), vec![2]);
+        storage.set(0, 0, 0, 5);
+        storage.set(1, 0, 0, 6);
+        storage.set(0, 0, 0, 2);
+        assert_eq!(storage.block_ids(), vec![2, 6]);
+    }
+
+    #[test]
+    fn from_ids_round_trips() {
+        let ids: Vec<BlockId> = (0..CHUNK_VOLUME).map(|i| (i % 300) as BlockId).collect();
+        let storage = ChunkStorage::from_ids(&ids).unwrap();
+        assert_eq!(bits_per_block(&storage), 9);
+        for (idx, &id) in ids.iter().enumerate() {
+            assert_eq!(storage.get_index(idx), id);
+        }
+    }
+
+    #[test]
+    fn from_ids_rejects_out_of_range_ids() {
+        let mut ids = vec![0; CHUNK_VOLUME];
+        ids[42] = MAX_BLOCK_ID + 1;
+        let err = ChunkStorage::from_ids(&ids).unwrap_err();
+        assert!(err.to_string().contains("index 42"));
+
+        ids[42] = MAX_BLOCK_ID;
+        assert!(ChunkStorage::from_ids(&ids).is_ok());
+        assert!(ChunkStorage::from_ids(&ids[1..]).is_err());
+    }
+
+    #[test]
+    fn legacy_floats_are_converted() {
+        let mut data = vec![0.0f32; CHUNK_VOLUME];
+        data[coord_to_index(4, 5, 6)] = 2.0;
+        let storage = ChunkStorage::from_legacy(&data).unwrap();
+        assert_eq!(storage.get(4, 5, 6), 2);
+        assert_eq!(storage.get(0, 0, 0), 0);
+    }
+
+    #[test]
+    fn legacy_rejects_lossy_ids() {
+        let mut data = vec![0.0f32; CHUNK_VOLUME];
+        data[10] = 1.5;
+        assert!(ChunkStorage::from_legacy(&data).is_err());
+
+        data[10] = -1.0;
+        assert!(ChunkStorage::from_legacy(&data).is_err());
+
+        assert!(ChunkStorage::from_legacy(&[0.0; 8]).is_err());
+    }
+}
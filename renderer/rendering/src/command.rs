@@ -0,0 +1,233 @@
+use instant::Instant;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use winit::event_loop::EventLoopProxy;
+
+use crate::block_types::BlockRegistry;
+use crate::camera::CameraMode;
+use crate::chunk_storage::ChunkStorage;
+use crate::entity_renderer::{EntityAnimation, EntityId, EntityState};
+use crate::world::{BlockEdit, ChunkPos};
+
+/// Ordre envoyé au thread de rendu, appliqué dans l'ordre d'envoi
+#[derive(Debug, Clone)]
+pub enum RenderCommand {
+    /// Placer la caméra (angles en degrés), quel que soit le mode
+    SetCamera {
+        position: [f32; 3],
+        yaw: f32,
+        pitch: f32,
+    },
+    /// Champ de vision vertical, en degrés
+    SetCameraFov(f32),
+    SetCameraMode(CameraMode),
+    /// Remplacer (ou charger) un chunk entier
+    LoadChunk(ChunkPos, ChunkStorage),
+    UnloadChunk(ChunkPos),
+    /// Modifier des blocs isolés
+    SetBlocks(Vec<BlockEdit>),
+    /// État complet des entités au tick daté, les autres sont retirées
+    SyncEntities(Vec<EntityState>, Instant),
+    /// Animation d'une entité, `None` pour la remettre au repos
+    SetEntityAnimation(EntityId, Option<EntityAnimation>),
+    /// Remplacer les types de blocs, puis régénérer tous les chunks
+    SetBlockRegistry(BlockRegistry),
+    /// Capturer la prochaine frame de la fenêtre dans ce fichier PNG
+    TakeScreenshot(PathBuf),
+}
+
+/// Événement winit : des commandes (ou un arrêt demandé) attendent dans le canal.
+/// Il ne porte rien, un réveil resté dans la file après un arrêt est donc sans effet.
+#[derive(Debug, Clone, Copy)]
+pub struct Wake;
+
+/// Ce qui réveille la boucle de rendu, `false` si elle est fermée
+trait Waker: Send {
+    fn wake(&self) -> bool;
+}
+
+impl Waker for EventLoopProxy<Wake> {
+    fn wake(&self) -> bool {
+        self.send_event(Wake).is_ok()
+    }
+}
+
+/// Canal vers la boucle de rendu : les commandes restent dans `backlog` jusqu'à ce
+/// qu'elle les prenne, qu'elle tourne ou non, et gardent ainsi leur ordre d'un lancement à l'autre
+#[derive(Default)]
+struct CommandChannel {
+    waker: Option<Box<dyn Waker>>,
+    backlog: Vec<RenderCommand>,
+}
+
+impl CommandChannel {
+    fn send(&mut self, command: RenderCommand) {
+        self.backlog.push(command);
+        self.wake();
+    }
+
+    fn send_if_connected(&mut self, command: RenderCommand) -> bool {
+        let connected = self.waker.is_some();
+        if connected {
+            self.send(command);
+        }
+        connected
+    }
+
+    fn wake(&mut self) {
+        if let Some(waker) = &self.waker
+            && !waker.wake()
+        {
+            self.waker = None;
+        }
+    }
+
+    fn connect(&mut self, waker: Box<dyn Waker>) -> Vec<RenderCommand> {
+        self.waker = Some(waker);
+        self.take()
+    }
+
+    fn take(&mut self) -> Vec<RenderCommand> {
+        std::mem::take(&mut self.backlog)
+    }
+
+    /// Remettre en tête les commandes prises mais pas appliquées avant l'arrêt
+    fn disconnect(&mut self, unapplied: Vec<RenderCommand>) {
+        self.waker = None;
+        self.backlog.splice(0..0, unapplied);
+    }
+}
+
+static CHANNEL: OnceLock<Mutex<CommandChannel>> = OnceLock::new();
+
+fn with_channel<T>(f: impl FnOnce(&mut CommandChannel) -> T) -> anyhow::Result<T> {
+    let mut channel = CHANNEL
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Failed to acquire lock on render command channel"))?;
+    Ok(f(&mut channel))
+}
+
+/// Envoyer une commande au thread de rendu (gardée pour le prochain lancement s'il ne tourne pas)
+pub fn send(command: RenderCommand) -> anyhow::Result<()> {
+    with_channel(|channel| channel.send(command))
+}
+
+/// Envoyer une commande seulement si la boucle de rendu tourne, renvoie si elle a été envoyée
+pub fn send_if_connected(command: RenderCommand) -> anyhow::Result<bool> {
+    with_channel(|channel| channel.send_if_connected(command))
+}
+
+/// Réveiller la boucle de rendu si elle tourne (ex. pour qu'elle voie un arrêt demandé)
+pub fn wake() -> anyhow::Result<()> {
+    with_channel(CommandChannel::wake)
+}
+
+/// Brancher la boucle de rendu, renvoie les commandes envoyées avant son lancement
+pub fn connect(proxy: EventLoopProxy<Wake>) -> anyhow::Result<Vec<RenderCommand>> {
+    with_channel(|channel| channel.connect(Box::new(proxy)))
+}
+
+/// Prendre les commandes arrivées depuis le dernier appel, dans l'ordre d'envoi
+pub fn take() -> anyhow::Result<Vec<RenderCommand>> {
+    with_channel(CommandChannel::take)
+}
+
+/// Copie des commandes en attente du prochain lancement, pour un rendu hors boucle
+pub fn pending() -> anyhow::Result<Vec<RenderCommand>> {
+    with_channel(|channel| channel.backlog.clone())
+}
+
+pub fn disconnect(unapplied: Vec<RenderCommand>) -> anyhow::Result<()> {
+    with_channel(|channel| channel.disconnect(unapplied))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    fn fovs(commands: &[RenderCommand]) -> Vec<f32> {
+        commands
+            .iter()
+            .map(|command| match command {
+                RenderCommand::SetCameraFov(fovy) => *fovy,
+                other => panic!("unexpected command {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn commands_wait_in_order_until_the_loop_runs() {
+        let mut channel = CommandChannel::default();
+        channel.send(RenderCommand::SetCameraFov(1.0));
+        channel.send(RenderCommand::SetCameraFov(2.0));
+        assert!(!channel.send_if_connected(RenderCommand::SetCameraFov(9.0)));
+
+        assert_eq!(fovs(&channel.backlog), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn unapplied_commands_go_back_to_the_front() {
+        let mut channel = CommandChannel::default();
+        channel.send(RenderCommand::SetCameraFov(3.0));
+        channel.disconnect(vec![
+            RenderCommand::SetCameraFov(1.0),
+            RenderCommand::SetCameraFov(2.0),
+        ]);
+
+        assert_eq!(fovs(&channel.backlog), vec![1.0, 2.0, 3.0]);
+    }
+
+    /// Boucle factice : compte les réveils, fermée quand `closed` passe à vrai
+    #[derive(Clone, Default)]
+    struct FakeLoop {
+        wakes: Arc<AtomicUsize>,
+        closed: Arc<AtomicBool>,
+    }
+
+    impl Waker for FakeLoop {
+        fn wake(&self) -> bool {
+            self.wakes.fetch_add(1, Ordering::SeqCst);
+            !self.closed.load(Ordering::SeqCst)
+        }
+    }
+
+    #[test]
+    fn commands_left_by_a_stopped_loop_run_first_and_in_order_on_restart() {
+        let mut channel = CommandChannel::default();
+        let first = FakeLoop::default();
+        channel.send(RenderCommand::SetCameraFov(1.0));
+        assert_eq!(fovs(&channel.connect(Box::new(first.clone()))), vec![1.0]);
+
+        // La boucle prend 2 mais s'arrête avant de l'appliquer ; 3 arrive entre sa sortie
+        // et la déconnexion, 4 après
+        channel.send(RenderCommand::SetCameraFov(2.0));
+        let taken = channel.take();
+        channel.send(RenderCommand::SetCameraFov(3.0));
+        first.closed.store(true, Ordering::SeqCst);
+        channel.disconnect(taken);
+        channel.send(RenderCommand::SetCameraFov(4.0));
+        channel.wake();
+        assert_eq!(first.wakes.load(Ordering::SeqCst), 2);
+
+        let second = FakeLoop::default();
+        assert_eq!(fovs(&channel.connect(Box::new(second.clone()))), vec![2.0, 3.0, 4.0]);
+        assert!(channel.take().is_empty());
+    }
+
+    #[test]
+    fn a_closed_loop_stops_receiving_wake_ups() {
+        let mut channel = CommandChannel::default();
+        let event_loop = FakeLoop::default();
+        event_loop.closed.store(true, Ordering::SeqCst);
+        channel.connect(Box::new(event_loop.clone()));
+
+        channel.send(RenderCommand::SetCameraFov(1.0));
+        channel.send(RenderCommand::SetCameraFov(2.0));
+
+        assert_eq!(event_loop.wakes.load(Ordering::SeqCst), 1);
+        assert_eq!(fovs(&channel.take()), vec![1.0, 2.0]);
+    }
+}
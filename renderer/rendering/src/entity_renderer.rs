@@ -0,0 +1,546 @@
+use anyhow::Context;
+use crate::render_backend::instance::Instance;
+use crate::render_backend::{InstanceBuffer, JointMatrix, Model, ModelData, SceneObject, SkinBuffer};
+use instant::Instant;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Identifiant d'entité de l'ECS Java
+pub type EntityId = i32;
+
+/// Modèles embarqués, indexés par leur `modelId` Java
+const BUILTIN_MODELS: [(&str, &[u8]); 2] = [
+    ("player", include_bytes!("model/player.glb")),
+    ("rocket", include_bytes!("model/rocket.glb")),
+];
+
+/// État d'une entité envoyé par Java à chaque synchronisation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityState {
+    pub id: EntityId,
+    pub model_id: u32,
+    pub transform: Instance,
+}
+
+/// Animation jouée en boucle par une entité, choisie par Java
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityAnimation {
+    /// Nom de l'animation dans le fichier glTF du modèle
+    pub clip: String,
+    /// Position dans l'animation à `start`, en secondes
+    pub time: f32,
+    /// Vitesse de lecture (1 = normale, 0 = figée à `time`)
+    pub speed: f32,
+    pub start: Instant,
+}
+
+impl EntityAnimation {
+    /// Position dans l'animation à `now`, ramenée dans [0, duration[
+    fn time_at(&self, now: Instant, duration: f32) -> f32 {
+        let elapsed = now.saturating_duration_since(self.start).as_secs_f32();
+        let time = self.time + elapsed * self.speed;
+        if duration > 0.0 { time.rem_euclid(duration) } else { 0.0 }
+    }
+}
+
+/// Instances d'un modèle, une case par entité (retrait en échangeant avec la dernière).
+///
+/// Chaque entité garde sa pose du tick précédent et du tick courant pour l'interpolation.
+#[derive(Default)]
+struct ModelInstances {
+    ids: Vec<EntityId>,
+    slots: HashMap<EntityId, usize>,
+    previous: Vec<Instance>,
+    current: Vec<Instance>,
+}
+
+impl ModelInstances {
+    /// Passer au tick suivant : la pose courante devient la pose précédente
+    fn advance_tick(&mut self) {
+        self.previous.clone_from(&self.current);
+    }
+
+    /// Ajouter ou déplacer une entité, renvoie true si son instance a changé
+    fn upsert(&mut self, id: EntityId, transform: Instance) -> bool {
+        match self.slots.get(&id) {
+            Some(&slot) if self.current[slot] == transform => false,
+            Some(&slot) => {
+                self.current[slot] = transform;
+                true
+            }
+            None => {
+                // Une nouvelle entité apparaît directement à sa place
+                self.slots.insert(id, self.ids.len());
+                self.ids.push(id);
+                self.previous.push(transform);
+                self.current.push(transform);
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, id: EntityId) -> bool {
+        let Some(slot) = self.slots.remove(&id) else {
+            return false;
+        };
+        self.ids.swap_remove(slot);
+        self.previous.swap_remove(slot);
+        self.current.swap_remove(slot);
+        if let Some(&moved) = self.ids.get(slot) {
+            self.slots.insert(moved, slot);
+        }
+        true
+    }
+
+    /// Poses à dessiner, `amount` allant de 0 (tick précédent) à 1 (tick courant)
+    fn interpolated(&self, amount: f32) -> Vec<Instance> {
+        self.previous
+            .iter()
+            .zip(&self.current)
+            .map(|(&previous, &current)| previous.interpolate(current, amount))
+            .collect()
+    }
+
+    fn is_moving(&self) -> bool {
+        self.previous != self.current
+    }
+
+    fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+/// Instances d'un modèle et l'objet GPU qui les dessine
+#[derive(Default)]
+struct ModelBatch {
+    instances: ModelInstances,
+    /// Poses de toutes les instances, un seul buffer GPU pour tous les sous-meshes
+    instance_buffer: Option<InstanceBuffer>,
+    /// Un objet par sous-mesh du modèle, dessinant les instances de `instance_buffer`
+    objects: Vec<SceneObject>,
+    /// Articulations de toutes les instances, partagées par les objets (modèles avec skin)
+    skin: Option<SkinBuffer>,
+    /// Le buffer GPU ne correspond plus aux poses (entités ajoutées, retirées ou en mouvement)
+    dirty: bool,
+}
+
+/// Dates des deux derniers ticks Java, pour placer chaque frame entre les deux
+#[derive(Debug, Clone, Copy)]
+struct TickClock {
+    previous: Instant,
+    current: Instant,
+}
+
+impl TickClock {
+    /// Avancement de `now` depuis le tick courant, en durées de tick (borné à [0, 1]).
+    ///
+    /// Le rendu a donc un tick de retard sur Java, ce qui évite d'extrapoler.
+    fn amount(&self, now: Instant) -> f32 {
+        let tick = self.current.saturating_duration_since(self.previous).as_secs_f32();
+        if tick <= 0.0 {
+            return 1.0;
+        }
+        (now.saturating_duration_since(self.current).as_secs_f32() / tick).clamp(0.0, 1.0)
+    }
+}
+
+/// Dessine les entités Java, avec un buffer d'instances par modèle
+pub struct EntityRenderer {
+    /// Modèle et matériau de chaque `modelId`, chargés au premier usage
+    models: HashMap<u32, Model>,
+    batches: HashMap<u32, ModelBatch>,
+    entity_models: HashMap<EntityId, u32>,
+    /// Modèles inconnus déjà signalés, pour ne pas répéter l'avertissement à chaque tick
+    unknown_models: HashSet<u32>,
+    /// Modèles dont le chargement a échoué : signalés une fois, leurs entités ne sont pas dessinées
+    failed_models: HashSet<u32>,
+    animations: HashMap<EntityId, EntityAnimation>,
+    /// Animations introuvables déjà signalées, par modèle
+    unknown_clips: HashSet<(u32, String)>,
+    clock: Option<TickClock>,
+}
+
+impl EntityRenderer {
+    pub fn new() -> Self {
+        Self {
+            models: HashMap::new(),
+            batches: HashMap::new(),
+            entity_models: HashMap::new(),
+            unknown_models: HashSet::new(),
+            failed_models: HashSet::new(),
+            animations: HashMap::new(),
+            unknown_clips: HashSet::new(),
+            clock: None,
+        }
+    }
+
+    /// Enregistrer le tick Java daté `tick` : celles absentes de `entities` sont retirées
+    pub fn sync(&mut self, entities: &[EntityState], tick: Instant) {
+        self.clock = Some(match self.clock {
+            Some(clock) => TickClock {
+                previous: clock.current,
+                current: tick.max(clock.current),
+            },
+            None => TickClock {
+                previous: tick,
+                current: tick,
+            },
+        });
+        for batch in self.batches.values_mut() {
+            batch.dirty |= batch.instances.is_moving();
+            batch.instances.advance_tick();
+        }
+
+        let mut seen = HashSet::with_capacity(entities.len());
+
+        for entity in entities {
+            if entity.model_id as usize >= BUILTIN_MODELS.len() {
+                if self.unknown_models.insert(entity.model_id) {
+                    log::warn!("Unknown model id {} for entity {}", entity.model_id, entity.id);
+                }
+                continue;
+            }
+            seen.insert(entity.id);
+
+            if let Some(previous) = self.entity_models.insert(entity.id, entity.model_id)
+                && previous != entity.model_id
+            {
+                self.remove_instance(previous, entity.id);
+            }
+
+            let batch = self.batches.entry(entity.model_id).or_default();
+            batch.dirty |= batch.instances.upsert(entity.id, entity.transform);
+        }
+
+        let gone: Vec<(EntityId, u32)> = self
+            .entity_models
+            .iter()
+            .filter(|(id, _)| !seen.contains(id))
+            .map(|(&id, &model_id)| (id, model_id))
+            .collect();
+        for (id, model_id) in gone {
+            self.entity_models.remove(&id);
+            self.animations.remove(&id);
+            self.remove_instance(model_id, id);
+        }
+    }
+
+    /// Jouer une animation sur une entité, ou la remettre au repos avec `None`
+    pub fn set_animation(&mut self, id: EntityId, animation: Option<EntityAnimation>) {
+        match animation {
+            Some(animation) => self.animations.insert(id, animation),
+            None => self.animations.remove(&id),
+        };
+        if let Some(batch) = self
+            .entity_models
+            .get(&id)
+            .and_then(|model_id| self.batches.get_mut(model_id))
+        {
+            batch.dirty = true;
+        }
+    }
+
+    fn remove_instance(&mut self, model_id: u32, id: EntityId) {
+        if let Some(batch) = self.batches.get_mut(&model_id) {
+            batch.dirty |= batch.instances.remove(id);
+        }
+    }
+
+    /// Envoyer au GPU les poses interpolées pour la frame dessinée à `now`.
+    ///
+    /// Un modèle qui ne se charge pas est signalé puis ignoré, sans empêcher les autres.
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, now: Instant) {
+        let amount = self.clock.map_or(1.0, |clock| clock.amount(now));
+
+        for (&model_id, batch) in self.batches.iter_mut() {
+            if !batch.dirty || self.failed_models.contains(&model_id) {
+                continue;
+            }
+            // Réécrire à chaque frame jusqu'à atteindre le tick courant (amount = 1)
+            batch.dirty = batch.instances.is_moving() && amount < 1.0;
+            if batch.instances.is_empty() {
+                batch.objects.clear();
+                batch.instance_buffer = None;
+                continue;
+            }
+
+            let model = match self.models.entry(model_id) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => match load_model(device, queue, model_id) {
+                    Ok(model) => entry.insert(model),
+                    Err(e) => {
+                        // Réessayer à chaque frame répéterait la même erreur
+                        log::error!(
+                            "Entities with model {} will not be drawn: {:#}",
+                            model_id,
+                            e
+                        );
+                        self.failed_models.insert(model_id);
+                        continue;
+                    }
+                },
+            };
+            let instances = batch.instances.interpolated(amount);
+            let joints = (model.skeleton.joint_count() > 0).then(|| {
+                let (joints, playing) = pose_joints(
+                    model_id,
+                    model,
+                    &batch.instances.ids,
+                    &self.animations,
+                    &mut self.unknown_clips,
+                    now,
+                );
+                // Une animation qui avance oblige à réécrire les articulations à chaque frame
+                batch.dirty |= playing;
+                joints
+            });
+
+            if let Some(instance_buffer) = &mut batch.instance_buffer
+                && !batch.objects.is_empty()
+            {
+                instance_buffer.replace(device, queue, instances);
+                for object in &mut batch.objects {
+                    object.set_instances(instance_buffer);
+                }
+                if let (Some(skin), Some(joints)) = (&mut batch.skin, &joints) {
+                    skin.replace(device, queue, joints);
+                    for object in &mut batch.objects {
+                        object.set_skin(skin.bind_group().clone());
+                    }
+                }
+                continue;
+            }
+
+            let instance_buffer =
+                batch.instance_buffer.insert(InstanceBuffer::new(device, instances));
+            batch.skin = joints
+                .map(|joints| SkinBuffer::new(device, model.skeleton.joint_count(), &joints));
+            batch.objects = model
+                .sub_meshes
+                .iter()
+                .map(|sub_mesh| {
+                    let material = model.materials[sub_mesh.material].clone();
+                    let object = SceneObject::new(model.mesh.clone(), material, instance_buffer)
+                        .with_index_range(sub_mesh.indices.clone());
+                    match &batch.skin {
+                        Some(skin) => object.with_skin(skin.bind_group().clone()),
+                        None => object,
+                    }
+                })
+                .collect();
+        }
+
+        self.batches.retain(|_, batch| !batch.instances.is_empty());
+    }
+
+    /// Les sous-meshes de chaque modèle utilisé, dessinant toutes ses entités
+    pub fn objects(&self) -> impl Iterator<Item = &SceneObject> {
+        self.batches.values().flat_map(|batch| batch.objects.iter())
+    }
+}
+
+impl Default for EntityRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Matrices d'articulations des entités `ids` d'un modèle avec skin, à la suite ;
+/// renvoie aussi si l'une des animations avance
+fn pose_joints(
+    model_id: u32,
+    model: &Model,
+    ids: &[EntityId],
+    animations: &HashMap<EntityId, EntityAnimation>,
+    unknown_clips: &mut HashSet<(u32, String)>,
+    now: Instant,
+) -> (Vec<JointMatrix>, bool) {
+    let mut joints = Vec::with_capacity(ids.len() * model.skeleton.joint_count());
+    let mut playing = false;
+    for id in ids {
+        let clip = animations.get(id).and_then(|animation| {
+            let clip = model.animations.iter().find(|clip| clip.name == animation.clip);
+            if clip.is_none() && unknown_clips.insert((model_id, animation.clip.clone())) {
+                log::warn!("Model {} has no animation named {}", model_id, animation.clip);
+            }
+            playing |= clip.is_some() && animation.speed != 0.0;
+            clip.map(|clip| (clip, animation.time_at(now, clip.duration)))
+        });
+        model.skeleton.joint_matrices(clip, &mut joints);
+    }
+    (joints, playing)
+}
+
+/// Fichier remplaçant le modèle embarqué `name` dans `directory` : `name.gltf` ou `name.glb`
+fn model_override(directory: &Path, name: &str) -> Option<PathBuf> {
+    ["gltf", "glb"]
+        .iter()
+        .map(|extension| directory.join(format!("{}.{}", name, extension)))
+        .find(|path| path.is_file())
+}
+
+/// Charger le modèle `model_id` depuis `MINI_GAME_MODEL_DIR` s'il s'y trouve, sinon
+/// depuis les modèles embarqués
+fn load_model(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    model_id: u32,
+) -> anyhow::Result<Model> {
+    let (name, bytes) = BUILTIN_MODELS[model_id as usize];
+    let path = std::env::var_os("MINI_GAME_MODEL_DIR")
+        .filter(|directory| !directory.is_empty())
+        .and_then(|directory| model_override(Path::new(&directory), name));
+    let data = match &path {
+        Some(path) => ModelData::load(path),
+        None => ModelData::from_glb_slice(bytes),
+    }
+    .with_context(|| format!("Failed to load model {} ({})", model_id, name))?;
+    log::debug!(
+        "Loaded entity model {} ({}): {} sub-meshes, {} vertices, {} materials",
+        model_id,
+        name,
+        data.sub_meshes.len(),
+        data.vertices.len(),
+        data.materials.len()
+    );
+    Model::new(device, queue, &data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{Deg, InnerSpace, One, Quaternion, Rotation3, Vector3};
+    use std::time::Duration;
+
+    fn entity(id: EntityId, model_id: u32, x: f32) -> EntityState {
+        EntityState {
+            id,
+            model_id,
+            transform: Instance::new(Vector3::new(x, 0.0, 0.0), Quaternion::one()),
+        }
+    }
+
+    fn positions(renderer: &EntityRenderer, model_id: u32) -> Vec<(EntityId, f32)> {
+        let instances = &renderer.batches[&model_id].instances;
+        let mut positions: Vec<_> = instances
+            .ids
+            .iter()
+            .zip(&instances.current)
+            .map(|(&id, instance)| (id, instance.position.x))
+            .collect();
+        positions.sort_by_key(|&(id, _)| id);
+        positions
+    }
+
+    #[test]
+    fn removal_keeps_slots_consistent() {
+        let mut instances = ModelInstances::default();
+        for id in 0..4 {
+            instances.upsert(id, entity(id, 0, id as f32).transform);
+        }
+        assert!(instances.remove(1));
+        assert!(!instances.remove(1));
+
+        for (&id, &slot) in &instances.slots {
+            assert_eq!(instances.ids[slot], id);
+            assert_eq!(instances.current[slot].position.x, id as f32);
+        }
+        assert!(!instances.upsert(3, entity(3, 0, 3.0).transform));
+    }
+
+    #[test]
+    fn sync_adds_moves_and_removes_entities() {
+        let mut renderer = EntityRenderer::new();
+        let now = Instant::now();
+        renderer.sync(&[entity(1, 0, 1.0), entity(2, 0, 2.0), entity(3, 1, 3.0)], now);
+        assert_eq!(positions(&renderer, 0), vec![(1, 1.0), (2, 2.0)]);
+        assert_eq!(positions(&renderer, 1), vec![(3, 3.0)]);
+
+        // L'entité 2 change de modèle, la 1 bouge, la 3 disparaît
+        renderer.sync(&[entity(1, 0, 5.0), entity(2, 1, 2.0)], now);
+        assert_eq!(positions(&renderer, 0), vec![(1, 5.0)]);
+        assert_eq!(positions(&renderer, 1), vec![(2, 2.0)]);
+        assert_eq!(renderer.entity_models.len(), 2);
+    }
+
+    #[test]
+    fn unknown_models_are_skipped() {
+        let mut renderer = EntityRenderer::new();
+        renderer.sync(&[entity(1, 99, 0.0)], Instant::now());
+        assert!(renderer.entity_models.is_empty());
+        assert!(renderer.unknown_models.contains(&99));
+    }
+
+    #[test]
+    fn frames_between_ticks_are_interpolated() {
+        let start = Instant::now();
+        let tick = Duration::from_millis(50);
+        let mut renderer = EntityRenderer::new();
+        renderer.sync(&[entity(1, 0, 0.0)], start);
+        renderer.sync(&[entity(1, 0, 10.0)], start + tick);
+
+        let clock = renderer.clock.unwrap();
+        assert_eq!(clock.amount(start + tick), 0.0);
+        assert!((clock.amount(start + tick + tick / 2) - 0.5).abs() < 1e-3);
+        assert_eq!(clock.amount(start + tick * 5), 1.0);
+
+        let instances = &renderer.batches[&0].instances;
+        assert!(instances.is_moving());
+        assert_eq!(instances.interpolated(0.25)[0].position.x, 2.5);
+    }
+
+    #[test]
+    fn animation_time_loops_over_the_clip() {
+        let start = Instant::now();
+        let animation = EntityAnimation {
+            clip: "walk".to_string(),
+            time: 0.5,
+            speed: 2.0,
+            start,
+        };
+        assert_eq!(animation.time_at(start, 2.0), 0.5);
+        assert_eq!(animation.time_at(start + Duration::from_millis(500), 2.0), 1.5);
+        assert_eq!(animation.time_at(start + Duration::from_secs(1), 2.0), 0.5);
+        assert_eq!(animation.time_at(start + Duration::from_secs(1), 0.0), 0.0);
+
+        let backwards = EntityAnimation { speed: -1.0, ..animation };
+        assert_eq!(backwards.time_at(start + Duration::from_secs(1), 2.0), 1.5);
+    }
+
+    #[test]
+    fn animations_are_dropped_with_their_entity() {
+        let mut renderer = EntityRenderer::new();
+        let now = Instant::now();
+        renderer.sync(&[entity(1, 0, 0.0), entity(2, 0, 0.0)], now);
+        for id in [1, 2] {
+            let animation = EntityAnimation {
+                clip: "walk".to_string(),
+                time: 0.0,
+                speed: 1.0,
+                start: now,
+            };
+            renderer.set_animation(id, Some(animation));
+        }
+
+        renderer.sync(&[entity(1, 0, 0.0)], now);
+        assert!(renderer.animations.contains_key(&1));
+        assert!(!renderer.animations.contains_key(&2));
+
+        renderer.set_animation(1, None);
+        assert!(renderer.animations.is_empty());
+    }
+
+    #[test]
+    fn rotations_are_slerped() {
+        let from = Instance::new(Vector3::new(0.0, 0.0, 0.0), Quaternion::one());
+        let to = Instance::new(
+            Vector3::new(0.0, 2.0, 0.0),
+            Quaternion::from_angle_y(Deg(90.0)),
+        );
+        let half = from.interpolate(to, 0.5);
+        assert_eq!(half.position.y, 1.0);
+        let expected = Quaternion::from_angle_y(Deg(45.0));
+        assert!((half.rotation - expected).magnitude() < 1e-5);
+    }
+}
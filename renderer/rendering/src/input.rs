@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, OnceLock};
+use winit::dpi::PhysicalPosition;
+use winit::event::MouseScrollDelta;
+use winit::keyboard::KeyCode;
+
+/// Nombre d'événements gardés au plus : au-delà, les plus anciens sont perdus
+pub const INPUT_QUEUE_CAPACITY: usize = 1024;
+
+/// Nombre de `double` par événement dans le tableau renvoyé à Java : (type, a, b)
+pub const PACKED_STRIDE: usize = 3;
+
+/// Entrée du joueur transmise à Java
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputEvent {
+    /// `code` suit `java.awt.event.KeyEvent.VK_*` (0 si la touche n'a pas d'équivalent)
+    Key { code: u32, pressed: bool },
+    /// Déplacement brut de la souris, indépendant du curseur
+    MouseMotion { dx: f64, dy: f64 },
+    /// Défilement en pixels (une ligne compte pour 100 pixels)
+    Scroll { dx: f64, dy: f64 },
+    Focus(bool),
+}
+
+impl InputEvent {
+    /// Valeurs de `InputEvents.KEY`, `MOUSE_MOTION`, `SCROLL` et `FOCUS` côté Java
+    fn kind(&self) -> f64 {
+        match self {
+            InputEvent::Key { .. } => 0.0,
+            InputEvent::MouseMotion { .. } => 1.0,
+            InputEvent::Scroll { .. } => 2.0,
+            InputEvent::Focus(_) => 3.0,
+        }
+    }
+
+    fn pack(&self, out: &mut Vec<f64>) {
+        let (a, b) = match *self {
+            InputEvent::Key { code, pressed } => (code as f64, pressed as u8 as f64),
+            InputEvent::MouseMotion { dx, dy } | InputEvent::Scroll { dx, dy } => (dx, dy),
+            InputEvent::Focus(focused) => (focused as u8 as f64, 0.0),
+        };
+        out.extend_from_slice(&[self.kind(), a, b]);
+    }
+
+    pub fn scroll(delta: &MouseScrollDelta) -> Self {
+        // Même convention que CameraController : une ligne fait environ 100 pixels
+        let (dx, dy) = match *delta {
+            MouseScrollDelta::LineDelta(x, y) => (x as f64 * 100.0, y as f64 * 100.0),
+            MouseScrollDelta::PixelDelta(PhysicalPosition { x, y }) => (x, y),
+        };
+        InputEvent::Scroll { dx, dy }
+    }
+}
+
+/// File bornée d'événements ; les mouvements de souris consécutifs sont fusionnés
+#[derive(Default)]
+struct InputQueue {
+    events: VecDeque<InputEvent>,
+    dropped: usize,
+}
+
+impl InputQueue {
+    fn push(&mut self, event: InputEvent) {
+        if let (
+            InputEvent::MouseMotion { dx, dy },
+            Some(InputEvent::MouseMotion { dx: last_dx, dy: last_dy }),
+        ) = (event, self.events.back_mut())
+        {
+            *last_dx += dx;
+            *last_dy += dy;
+            return;
+        }
+
+        if self.events.len() == INPUT_QUEUE_CAPACITY {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+
+    fn drain_packed(&mut self) -> Vec<f64> {
+        if self.dropped > 0 {
+            log::warn!("Input queue full, dropped {} events not polled by Java", self.dropped);
+            self.dropped = 0;
+        }
+
+        let mut packed = Vec::with_capacity(self.events.len() * PACKED_STRIDE);
+        for event in self.events.drain(..) {
+            event.pack(&mut packed);
+        }
+        packed
+    }
+}
+
+static INPUT_QUEUE: OnceLock<Mutex<InputQueue>> = OnceLock::new();
+
+fn get_input_queue() -> &'static Mutex<InputQueue> {
+    INPUT_QUEUE.get_or_init(Default::default)
+}
+
+/// Ajouter un événement pour Java (ignoré si la file est verrouillée par un thread mort)
+pub fn push_input_event(event: InputEvent) {
+    if let Ok(mut queue) = get_input_queue().lock() {
+        queue.push(event);
+    }
+}
+
+/// Vider la file, au format (type, a, b) par événement
+pub fn drain_packed() -> anyhow::Result<Vec<f64>> {
+    let mut queue = get_input_queue()
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Failed to acquire lock on input queue"))?;
+    Ok(queue.drain_packed())
+}
+
+/// Code `java.awt.event.KeyEvent.VK_*` d'une touche physique, 0 (`VK_UNDEFINED`) sinon
+pub fn java_key_code(key: KeyCode) -> u32 {
+    use KeyCode::*;
+
+    const LETTERS: [KeyCode; 26] = [
+        KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN,
+        KeyO, KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ,
+    ];
+    const DIGITS: [KeyCode; 10] = [
+        Digit0, Digit1, Digit2, Digit3, Digit4, Digit5, Digit6, Digit7, Digit8, Digit9,
+    ];
+    const FUNCTION_KEYS: [KeyCode; 12] = [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12];
+
+    if let Some(i) = LETTERS.iter().position(|&k| k == key) {
+        return 'A' as u32 + i as u32;
+    }
+    if let Some(i) = DIGITS.iter().position(|&k| k == key) {
+        return '0' as u32 + i as u32;
+    }
+    if let Some(i) = FUNCTION_KEYS.iter().position(|&k| k == key) {
+        return 112 + i as u32;
+    }
+
+    match key {
+        Backspace => 8,
+        Tab => 9,
+        Enter | NumpadEnter => 10,
+        ShiftLeft | ShiftRight => 16,
+        ControlLeft | ControlRight => 17,
+        AltLeft | AltRight => 18,
+        Escape => 27,
+        Space => 32,
+        ArrowLeft => 37,
+        ArrowUp => 38,
+        ArrowRight => 39,
+        ArrowDown => 40,
+        Delete => 127,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_codes_match_java_constants() {
+        assert_eq!(java_key_code(KeyCode::KeyA), 65);
+        assert_eq!(java_key_code(KeyCode::KeyW), 87);
+        assert_eq!(java_key_code(KeyCode::Digit7), 55);
+        assert_eq!(java_key_code(KeyCode::F2), 113);
+        assert_eq!(java_key_code(KeyCode::ShiftRight), 16);
+        assert_eq!(java_key_code(KeyCode::MediaPlayPause), 0);
+    }
+
+    #[test]
+    fn consecutive_mouse_motion_is_merged() {
+        let mut queue = InputQueue::default();
+        queue.push(InputEvent::MouseMotion { dx: 1.0, dy: 2.0 });
+        queue.push(InputEvent::MouseMotion { dx: 3.0, dy: -1.0 });
+        queue.push(InputEvent::Key { code: 87, pressed: true });
+        queue.push(InputEvent::MouseMotion { dx: 5.0, dy: 0.0 });
+
+        assert_eq!(
+            queue.drain_packed(),
+            vec![1.0, 4.0, 1.0, 0.0, 87.0, 1.0, 1.0, 5.0, 0.0]
+        );
+        assert!(queue.drain_packed().is_empty());
+    }
+
+    #[test]
+    fn full_queue_drops_oldest_events() {
+        let mut queue = InputQueue::default();
+        for i in 0..INPUT_QUEUE_CAPACITY + 2 {
+            queue.push(InputEvent::Key { code: i as u32, pressed: false });
+        }
+
+        let packed = queue.drain_packed();
+        assert_eq!(packed.len(), INPUT_QUEUE_CAPACITY * PACKED_STRIDE);
+        assert_eq!(packed[1], 2.0);
+    }
+}
@@ -0,0 +1,100 @@
+use jni::JNIEnv;
+use jni::objects::{JObject, JThrowable, JValue};
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Classe Java levée pour toute erreur du moteur de rendu
+const RENDERER_EXCEPTION: &str = "RendererException";
+const FALLBACK_EXCEPTION: &str = "java/lang/RuntimeException";
+
+/// Exécuter le corps d'une fonction JNI sans jamais laisser remonter d'erreur ni de panique.
+///
+/// Une erreur ou une panique est convertie en `RendererException` côté Java,
+/// et `default` est renvoyé (la JVM ignore la valeur quand une exception est levée).
+pub fn jni_guard<'local, T, F>(env: &mut JNIEnv<'local>, default: T, body: F) -> T
+where
+    F: FnOnce(&mut JNIEnv<'local>) -> anyhow::Result<T>,
+{
+    let result = panic::catch_unwind(AssertUnwindSafe(|| body(env)));
+
+    let error = match result {
+        Ok(Ok(value)) => return value,
+        Ok(Err(error)) => error,
+        Err(payload) => anyhow::anyhow!("Renderer panicked: {}", panic_message(&*payload)),
+    };
+
+    log::error!("JNI call failed: {:#}", error);
+    throw_renderer_exception(env, &error);
+    default
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Lever une `RendererException` par niveau de la chaîne d'erreurs, chacune cause de la précédente
+fn throw_renderer_exception(env: &mut JNIEnv, error: &anyhow::Error) {
+    // Une exception Java déjà levée (appel JNI échoué) devient la cause la plus profonde
+    let pending = match env.exception_occurred() {
+        Ok(exception) if !exception.is_null() => {
+            let _ = env.exception_clear();
+            Some(exception)
+        }
+        _ => None,
+    };
+
+    if let Err(e) = build_exception_chain(env, error, pending) {
+        log::warn!("Could not build {}: {}", RENDERER_EXCEPTION, e);
+        let _ = env.exception_clear();
+        let _ = env.throw_new(FALLBACK_EXCEPTION, format!("{:#}", error));
+    }
+}
+
+fn build_exception_chain<'local>(
+    env: &mut JNIEnv<'local>,
+    error: &anyhow::Error,
+    pending: Option<JThrowable<'local>>,
+) -> jni::errors::Result<()> {
+    let cause = pending.map(JObject::from).unwrap_or_default();
+    let exception = new_renderer_exception(env, error, cause)?;
+    env.throw(JThrowable::from(exception))
+}
+
+/// Créer (sans la lever) une `RendererException` pour une erreur et toute sa chaîne de causes
+pub fn new_renderer_exception<'local>(
+    env: &mut JNIEnv<'local>,
+    error: &anyhow::Error,
+    cause: JObject<'local>,
+) -> jni::errors::Result<JObject<'local>> {
+    let mut cause = cause;
+    let messages: Vec<String> = error.chain().map(|e| e.to_string()).collect();
+    for message in messages.iter().rev() {
+        let message = env.new_string(message)?;
+        cause = env.new_object(
+            RENDERER_EXCEPTION,
+            "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+            &[JValue::Object(&message), JValue::Object(&cause)],
+        )?;
+    }
+    Ok(cause)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panic_payloads_become_messages() {
+        let payload = panic::catch_unwind(|| panic!("boom {}", 42)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "boom 42");
+
+        let payload = panic::catch_unwind(|| panic!("static")).unwrap_err();
+        assert_eq!(panic_message(&*payload), "static");
+    }
+}
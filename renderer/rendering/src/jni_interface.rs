@@ -1,82 +1,438 @@
+use anyhow::Context;
 use jni::JNIEnv;
-use jni::objects::{JClass, JFloatArray};
-use jni::sys::jfloatArray;
-use std::sync::{RwLock, OnceLock, Mutex};
-
-static GLOBAL_CHUNK_DATA: OnceLock<RwLock<Vec<f32>>> = OnceLock::new();
-static CHUNK_UPDATED: OnceLock<Mutex<bool>> = OnceLock::new();
-
-/// Initialiser le stockage global des données de chunk
-fn get_chunk_storage() -> &'static RwLock<Vec<f32>> {
-    GLOBAL_CHUNK_DATA.get_or_init(|| {
-        // Initialiser avec un chunk vide de 32x32x32
-        RwLock::new(vec![0.0; 32 * 32 * 32])
+use cgmath::{InnerSpace, Quaternion, Vector3};
+use jni::objects::{JByteBuffer, JClass, JDoubleArray, JFloatArray, JIntArray, JString};
+use jni::sys::{jdouble, jint};
+use std::collections::HashSet;
+
+use crate::block_types::{self, BlockRegistry};
+use crate::camera::CameraMode;
+use crate::command::{self, RenderCommand};
+use crate::entity_renderer::{EntityAnimation, EntityState};
+use crate::render_backend::instance::Instance;
+use crate::chunk_storage::{BlockId, ChunkStorage, CHUNK_VOLUME, MAX_BLOCK_ID};
+use crate::jni_error::jni_guard;
+use crate::world::{BlockEdit, ChunkPos};
+
+/// Lire un tableau de blocs Java (ancien format `float[]`) en ChunkStorage
+fn read_chunk_array(env: &JNIEnv, java_array: &JFloatArray) -> anyhow::Result<ChunkStorage> {
+    let len = env
+        .get_array_length(java_array)
+        .context("Failed to get array length")?;
+    let mut rust_array = vec![0.0f32; len as usize];
+    env.get_float_array_region(java_array, 0, &mut rust_array)
+        .context("Failed to get float array region")?;
+
+    ChunkStorage::from_legacy(&rust_array).context("Invalid chunk data")
+}
+
+/// Recevoir les données du chunk d'origine depuis Java
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_updateChunk<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    java_array: JFloatArray<'local>,
+) {
+    jni_guard(&mut env, (), |env| {
+        let data = read_chunk_array(env, &java_array)?;
+        block_types::ensure_registered(data.block_ids())?;
+        command::send(RenderCommand::LoadChunk(ChunkPos::default(), data))
+    })
+}
+
+/// Recevoir les données d'un chunk aux coordonnées (cx, cy, cz) depuis Java
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_updateChunkAt<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    cx: jint,
+    cy: jint,
+    cz: jint,
+    java_array: JFloatArray<'local>,
+) {
+    jni_guard(&mut env, (), |env| {
+        let pos = ChunkPos::new(cx, cy, cz);
+        let data = read_chunk_array(env, &java_array)
+            .and_then(|data| block_types::ensure_registered(data.block_ids()).map(|_| data))
+            .with_context(|| format!("Rejected chunk {:?}", pos))?;
+        command::send(RenderCommand::LoadChunk(pos, data))
     })
 }
 
-/// Initialiser le flag de mise à jour
-fn get_update_flag() -> &'static Mutex<bool> {
-    CHUNK_UPDATED.get_or_init(|| Mutex::new(false))
+/// Lire un ByteBuffer direct de `u16` (ordre natif) sans copie intermédiaire
+fn read_chunk_buffer(env: &JNIEnv, buffer: &JByteBuffer) -> anyhow::Result<ChunkStorage> {
+    let capacity = env.get_direct_buffer_capacity(buffer)?;
+    anyhow::ensure!(
+        capacity == CHUNK_VOLUME * size_of::<BlockId>(),
+        "Chunk buffer has {} bytes, expected {} ({} u16 block ids)",
+        capacity,
+        CHUNK_VOLUME * size_of::<BlockId>(),
+        CHUNK_VOLUME
+    );
+
+    let address = env.get_direct_buffer_address(buffer)?;
+    anyhow::ensure!(
+        address.align_offset(align_of::<BlockId>()) == 0,
+        "Chunk buffer address is not aligned for u16"
+    );
+
+    // SAFETY: l'adresse est non nulle, alignée et couvre `capacity` octets d'après la JVM ;
+    // le buffer n'est lu que pendant cet appel, avant d'être copié dans la palette
+    let ids = unsafe { std::slice::from_raw_parts(address as *const BlockId, CHUNK_VOLUME) };
+    ChunkStorage::from_ids(ids)
 }
 
-/// Recevoir les données du chunk depuis Java
+/// Recevoir un chunk sous forme de ByteBuffer direct de `u16`
 #[allow(non_snake_case)]
 #[unsafe(no_mangle)]
-pub extern "system" fn Java_Teste_updateChunk<'local>(
+pub extern "system" fn Java_Teste_updateChunkBuffer<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    cx: jint,
+    cy: jint,
+    cz: jint,
+    buffer: JByteBuffer<'local>,
+) {
+    jni_guard(&mut env, (), |env| {
+        let pos = ChunkPos::new(cx, cy, cz);
+        let storage = read_chunk_buffer(env, &buffer)
+            .and_then(|data| block_types::ensure_registered(data.block_ids()).map(|_| data))
+            .with_context(|| format!("Rejected chunk buffer {:?}", pos))?;
+        command::send(RenderCommand::LoadChunk(pos, storage))
+    })
+}
+
+/// Décharger le chunk aux coordonnées (cx, cy, cz)
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_unloadChunk<'local>(
     mut env: JNIEnv<'local>,
     _class: JClass<'local>,
-    chunk_data: jfloatArray,
+    cx: jint,
+    cy: jint,
+    cz: jint,
 ) {
-    // Convertir le tableau Java en Vec<f32> Rust
-    let java_array = unsafe { JFloatArray::from_raw(chunk_data) };
-
-    match env.get_array_length(&java_array) {
-        Ok(len) => {
-            let mut rust_array = vec![0.0f32; len as usize];
-
-            match env.get_float_array_region(&java_array, 0, &mut rust_array) {
-                Ok(_) => {
-                    // Mettre à jour le stockage global
-                    if let Ok(mut storage) = get_chunk_storage().write() {
-                        *storage = rust_array;
-
-                        // Marquer qu'une mise à jour est nécessaire
-                        if let Ok(mut flag) = get_update_flag().lock() {
-                            *flag = true;
-                        }
-
-                        println!("Chunk data updated successfully: {} blocks", len);
-                    } else {
-                        eprintln!("Failed to acquire write lock on chunk storage");
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to get float array region: {:?}", e);
-                }
-            }
-        }
-        Err(e) => {
-            eprintln!("Failed to get array length: {:?}", e);
-        }
+    jni_guard(&mut env, (), |_| {
+        command::send(RenderCommand::UnloadChunk(ChunkPos::new(cx, cy, cz)))
+    })
+}
+
+/// Convertir un id Java en BlockId, erreur s'il est hors de [0, MAX_BLOCK_ID]
+fn to_block_id(block_id: jint) -> anyhow::Result<BlockId> {
+    BlockId::try_from(block_id)
+        .ok()
+        .filter(|&id| id <= MAX_BLOCK_ID)
+        .with_context(|| format!("Invalid block id {}, expected 0..={}", block_id, MAX_BLOCK_ID))
+}
+
+/// Modifier un bloc aux coordonnées du monde (x, y, z)
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_setBlock<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    x: jint,
+    y: jint,
+    z: jint,
+    block_id: jint,
+) {
+    jni_guard(&mut env, (), |_| {
+        let block_id = to_block_id(block_id)
+            .and_then(|id| block_types::ensure_registered([id]).map(|_| id))
+            .with_context(|| format!("Rejected block at ({}, {}, {})", x, y, z))?;
+        command::send(RenderCommand::SetBlocks(vec![BlockEdit { x, y, z, block_id }]))
+    })
+}
+
+/// Modifier plusieurs blocs : tableau de quadruplets (x, y, z, id)
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_setBlocks<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    packed: JIntArray<'local>,
+) {
+    jni_guard(&mut env, (), |env| {
+        let len = env
+            .get_array_length(&packed)
+            .context("Failed to get array length")? as usize;
+        anyhow::ensure!(
+            len.is_multiple_of(4),
+            "setBlocks expects (x, y, z, id) quadruplets, got {} ints",
+            len
+        );
+
+        let mut values = vec![0; len];
+        env.get_int_array_region(&packed, 0, &mut values)
+            .context("Failed to get int array region")?;
+
+        let edits = values
+            .chunks_exact(4)
+            .map(|edit| {
+                let block_id = to_block_id(edit[3]).with_context(|| {
+                    format!("Rejected block at ({}, {}, {})", edit[0], edit[1], edit[2])
+                })?;
+                Ok(BlockEdit {
+                    x: edit[0],
+                    y: edit[1],
+                    z: edit[2],
+                    block_id,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        block_types::ensure_registered(edits.iter().map(|edit| edit.block_id))
+            .context("Rejected setBlocks")?;
+
+        command::send(RenderCommand::SetBlocks(edits))
+    })
+}
+
+/// Valeurs de `Teste.CAMERA_FREE_FLY` et `Teste.CAMERA_DRIVEN`
+fn to_camera_mode(mode: jint) -> anyhow::Result<CameraMode> {
+    match mode {
+        0 => Ok(CameraMode::FreeFly),
+        1 => Ok(CameraMode::Driven),
+        _ => anyhow::bail!("Invalid camera mode {}, expected 0 (free-fly) or 1 (driven)", mode),
     }
 }
 
-/// Récupérer les données du chunk depuis le stockage global
-pub fn get_chunk_data() -> Vec<f32> {
-    get_chunk_storage()
-        .read()
-        .map(|data| data.clone())
-        .unwrap_or_else(|_| vec![0.0; 32 * 32 * 32])
+/// Choisir qui contrôle la caméra : 0 = vol libre, 1 = imposée par Java
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_setCameraMode<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    mode: jint,
+) {
+    jni_guard(&mut env, (), |_| {
+        command::send(RenderCommand::SetCameraMode(to_camera_mode(mode)?))
+    })
+}
+
+/// Placer la caméra (position du monde, lacet et tangage en degrés)
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_setCamera<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    x: jdouble,
+    y: jdouble,
+    z: jdouble,
+    yaw: jdouble,
+    pitch: jdouble,
+) {
+    jni_guard(&mut env, (), |_| {
+        anyhow::ensure!(
+            [x, y, z, yaw, pitch].iter().all(|v| v.is_finite()),
+            "Camera pose must be finite, got position ({}, {}, {}), yaw {}, pitch {}",
+            x,
+            y,
+            z,
+            yaw,
+            pitch
+        );
+        command::send(RenderCommand::SetCamera {
+            position: [x as f32, y as f32, z as f32],
+            yaw: yaw as f32,
+            pitch: pitch as f32,
+        })
+    })
+}
+
+/// Changer le champ de vision vertical, en degrés dans ]0, 180[
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_setCameraFov<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    fov: jdouble,
+) {
+    jni_guard(&mut env, (), |_| {
+        anyhow::ensure!(
+            fov > 0.0 && fov < 180.0,
+            "Invalid camera fov {}, expected degrees in (0, 180)",
+            fov
+        );
+        command::send(RenderCommand::SetCameraFov(fov as f32))
+    })
+}
+
+/// Lire un tableau Java de `len` éléments
+fn read_double_array(
+    env: &JNIEnv,
+    array: &JDoubleArray,
+    name: &str,
+    len: usize,
+) -> anyhow::Result<Vec<f64>> {
+    let actual = env.get_array_length(array)? as usize;
+    anyhow::ensure!(actual == len, "{} has {} values, expected {}", name, actual, len);
+    let mut values = vec![0.0; len];
+    env.get_double_array_region(array, 0, &mut values)?;
+    Ok(values)
 }
 
-/// Vérifier si le chunk a été mis à jour et réinitialiser le flag
-pub fn check_and_clear_update_flag() -> bool {
-    get_update_flag()
-        .lock()
-        .map(|mut flag| {
-            let updated = *flag;
-            *flag = false;
-            updated
+/// Convertir les tableaux de `syncEntities` en états d'entités, avec validation
+fn read_entities(
+    env: &JNIEnv,
+    ids: &JIntArray,
+    positions: &JDoubleArray,
+    rotations: &JDoubleArray,
+    model_ids: &JIntArray,
+) -> anyhow::Result<Vec<EntityState>> {
+    let count = env.get_array_length(ids)? as usize;
+    let mut entity_ids = vec![0; count];
+    env.get_int_array_region(ids, 0, &mut entity_ids)?;
+
+    anyhow::ensure!(
+        env.get_array_length(model_ids)? as usize == count,
+        "modelIds must have one value per entity ({})",
+        count
+    );
+    let mut models = vec![0; count];
+    env.get_int_array_region(model_ids, 0, &mut models)?;
+
+    let positions = read_double_array(env, positions, "positions", count * 3)?;
+    let rotations = read_double_array(env, rotations, "rotations", count * 4)?;
+
+    let mut seen = HashSet::with_capacity(count);
+    (0..count)
+        .map(|i| {
+            let id = entity_ids[i];
+            anyhow::ensure!(seen.insert(id), "Duplicate entity id {}", id);
+            let model_id = u32::try_from(models[i])
+                .map_err(|_| anyhow::anyhow!("Invalid model id {} for entity {}", models[i], id))?;
+
+            let p = &positions[i * 3..i * 3 + 3];
+            let r = &rotations[i * 4..i * 4 + 4];
+            anyhow::ensure!(
+                p.iter().chain(r).all(|v| v.is_finite()),
+                "Entity {} has a non-finite transform",
+                id
+            );
+            // Quaternion JOML (x, y, z, w)
+            let rotation = Quaternion::new(r[3] as f32, r[0] as f32, r[1] as f32, r[2] as f32);
+            anyhow::ensure!(
+                rotation.magnitude2() > f32::EPSILON,
+                "Entity {} has a zero rotation quaternion",
+                id
+            );
+
+            Ok(EntityState {
+                id,
+                model_id,
+                transform: Instance::new(
+                    Vector3::new(p[0] as f32, p[1] as f32, p[2] as f32),
+                    rotation.normalize(),
+                ),
+            })
         })
-        .unwrap_or(false)
-}
\ No newline at end of file
+        .collect()
+}
+
+/// Envoyer l'état complet des entités : positions (x, y, z) et rotations (x, y, z, w) à plat
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_syncEntities<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    ids: JIntArray<'local>,
+    positions: JDoubleArray<'local>,
+    rotations: JDoubleArray<'local>,
+    model_ids: JIntArray<'local>,
+) {
+    jni_guard(&mut env, (), |env| {
+        // Le tick est daté à l'appel, le rendu interpole entre les deux derniers
+        let tick = instant::Instant::now();
+        let entities = read_entities(env, &ids, &positions, &rotations, &model_ids)?;
+        command::send(RenderCommand::SyncEntities(entities, tick))
+    })
+}
+
+/// Jouer en boucle l'animation glTF `clip` sur une entité, à partir de `time` secondes
+/// et à la vitesse `speed` ; `clip` nul remet l'entité au repos
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_setEntityAnimation<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    entity_id: jint,
+    clip: JString<'local>,
+    time: jdouble,
+    speed: jdouble,
+) {
+    jni_guard(&mut env, (), |env| {
+        let start = instant::Instant::now();
+        let animation = if clip.is_null() {
+            None
+        } else {
+            anyhow::ensure!(
+                time.is_finite() && speed.is_finite(),
+                "Animation time and speed must be finite, got {} and {}",
+                time,
+                speed
+            );
+            Some(EntityAnimation {
+                clip: env.get_string(&clip)?.into(),
+                time: time as f32,
+                speed: speed as f32,
+                start,
+            })
+        };
+        command::send(RenderCommand::SetEntityAnimation(entity_id, animation))
+    })
+}
+
+/// Lire une chaîne Java non nulle
+fn read_string(env: &mut JNIEnv, value: &JString, what: &str) -> anyhow::Result<String> {
+    anyhow::ensure!(!value.is_null(), "{} is null", what);
+    Ok(env.get_string(value)?.into())
+}
+
+/// Remplacer les types de blocs par un registre JSON (même format que `blocks.json`).
+/// Un registre invalide lève une exception et l'ancien reste utilisé.
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_setBlockRegistry<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    json: JString<'local>,
+) {
+    jni_guard(&mut env, (), |env| {
+        let json = read_string(env, &json, "Block registry")?;
+        let registry = BlockRegistry::from_json(&json).context("Invalid block registry")?;
+        block_types::set_current(registry.clone())?;
+        command::send(RenderCommand::SetBlockRegistry(registry))
+    })
+}
+
+/// Remplacer les types de blocs par ceux du fichier `path`
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_loadBlockRegistry<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    path: JString<'local>,
+) {
+    jni_guard(&mut env, (), |env| {
+        let path = read_string(env, &path, "Block registry path")?;
+        let registry = BlockRegistry::load(std::path::Path::new(&path))?;
+        block_types::set_current(registry.clone())?;
+        command::send(RenderCommand::SetBlockRegistry(registry))
+    })
+}
+
+/// ID du bloc `name` dans le registre en vigueur, exception si le nom est inconnu
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_blockId<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    name: JString<'local>,
+) -> jint {
+    jni_guard(&mut env, 0, |env| {
+        let name = read_string(env, &name, "Block name")?;
+        Ok(block_types::current_id_of(&name)? as jint)
+    })
+}
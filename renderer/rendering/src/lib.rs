@@ -1,15 +1,27 @@
 mod render_backend;
 mod texture;
 mod camera;
+mod light;
 mod block_types;
+mod chunk_mesher;
+mod chunk_storage;
 mod chunk_renderer;
+mod mesh_worker;
+mod world;
+mod entity_renderer;
 mod jni_interface;
+mod jni_error;
+mod command;
+mod input;
+mod lifecycle;
 
-use render_backend::State;
+use render_backend::{HeadlessRenderer, State};
+use command::{RenderCommand, Wake};
+use input::InputEvent;
 
 use std::sync::Arc;
 use jni::JNIEnv;
-use jni::objects::JClass;
+use jni::objects::{JClass, JObject, JString};
 use winit::{
     application::ApplicationHandler,
     event::*,
@@ -19,16 +31,28 @@ use winit::{
 };
 use winit::dpi::PhysicalSize;
 use winit::window::{CursorGrabMode, WindowId};
-use jni::sys::jdouble;
+use jni::sys::{jboolean, jdoubleArray, jstring};
 use winit::keyboard::KeyCode;
-use std::sync::{OnceLock, RwLock};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+#[cfg(not(target_arch = "wasm32"))]
+use winit::platform::run_on_demand::EventLoopExtRunOnDemand;
 
-static GLOBAL_POSITION: OnceLock<RwLock<(f32, f32, f32)>> = OnceLock::new();
+/// Taille de la fenêtre à l'ouverture, et des images rendues sans fenêtre
+pub const DEFAULT_WIDTH: u32 = 1200;
+pub const DEFAULT_HEIGHT: u32 = 800;
+
+thread_local! {
+    /// winit interdit de recréer une boucle d'événements : elle est gardée pour un redémarrage
+    static EVENT_LOOP: RefCell<Option<EventLoop<Wake>>> = const { RefCell::new(None) };
+}
 
 pub struct App {
     state: Option<State>,
     last_time: instant::Instant,
     would_block: bool,
+    /// Commandes reçues, appliquées au début de la prochaine frame
+    commands: VecDeque<RenderCommand>,
 }
 
 impl App {
@@ -37,17 +61,24 @@ impl App {
             state: None,
             last_time: instant::Instant::now(),
             would_block: false,
+            commands: VecDeque::new(),
         }
     }
 }
 
-impl ApplicationHandler<State> for App {
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApplicationHandler<Wake> for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         let mut window_attributes = Window::default_attributes();
         window_attributes.inner_size = Some(
             PhysicalSize {
-                width: 1200,
-                height: 800,
+                width: DEFAULT_WIDTH,
+                height: DEFAULT_HEIGHT,
             }
                 .into(),
         );
@@ -72,13 +103,14 @@ impl ApplicationHandler<State> for App {
         }
     }
 
-    fn user_event(&mut self, _event_loop: &ActiveEventLoop, mut event: State) {
-        event.window.request_redraw();
-        event.resize(
-            event.window.inner_size().width,
-            event.window.inner_size().height,
-        );
-        self.state = Some(event);
+    fn user_event(&mut self, _event_loop: &ActiveEventLoop, _wake: Wake) {
+        match command::take() {
+            Ok(commands) => self.commands.extend(commands),
+            Err(e) => log::error!("Failed to take render commands: {}", e),
+        }
+        if let Some(state) = &self.state {
+            state.window.request_redraw();
+        }
     }
 
     fn window_event(
@@ -94,21 +126,18 @@ impl ApplicationHandler<State> for App {
 
         match event {
             WindowEvent::CloseRequested => event_loop.exit(),
+            WindowEvent::Focused(focused) => input::push_input_event(InputEvent::Focus(focused)),
             WindowEvent::Resized(size) => state.resize(size.width, size.height),
             WindowEvent::RedrawRequested => {
                 let dt = self.last_time.elapsed();
                 self.last_time = instant::Instant::now();
-                let pos = get_position();
 
-                // Vérifier si le chunk doit être mis à jour
-                if jni_interface::check_and_clear_update_flag() {
-                    if let Err(e) = state.update_chunk_from_java() {
-                        log::error!("Failed to update chunk: {}", e);
-                    }
+                // Appliquer les commandes envoyées par Java
+                if let Err(e) = state.apply_commands(self.commands.drain(..)) {
+                    log::error!("Failed to apply render commands: {}", e);
                 }
 
                 state.update(dt);
-                state.update_instance(pos);
 
                 match state.render() {
                     Ok(_) => {}
@@ -122,29 +151,47 @@ impl ApplicationHandler<State> for App {
                 }
             }
             WindowEvent::MouseWheel { delta, .. } => {
-                state.camera_controller.handle_mouse_scroll(&delta);
+                input::push_input_event(InputEvent::scroll(&delta));
+                state.camera_controller().handle_mouse_scroll(&delta);
             }
             WindowEvent::KeyboardInput {
                 event:
                 KeyEvent {
                     physical_key: PhysicalKey::Code(code),
                     state: key_state,
+                    repeat,
                     ..
                 },
                 ..
-            } => match (code, key_state.is_pressed()) {
-                (KeyCode::Escape, true) => event_loop.exit(),
-                (KeyCode::KeyR, true) => {
-                    if !self.would_block {
-                        self.would_block = true;
-                        let _ = state.window.set_cursor_grab(CursorGrabMode::None);
-                    } else {
-                        self.would_block = false;
-                        let _ = state.window.set_cursor_grab(CursorGrabMode::Locked);
+            } => {
+                if !repeat {
+                    input::push_input_event(InputEvent::Key {
+                        code: input::java_key_code(code),
+                        pressed: key_state.is_pressed(),
+                    });
+                }
+
+                match (code, key_state.is_pressed()) {
+                    (KeyCode::Escape, true) => event_loop.exit(),
+                    (KeyCode::F2, true) => {
+                        let directory = render_backend::screenshot::default_directory();
+                        state.request_screenshot(render_backend::screenshot::timestamped_path(
+                            &directory,
+                            std::time::SystemTime::now(),
+                        ));
                     }
+                    (KeyCode::KeyR, true) => {
+                        if !self.would_block {
+                            self.would_block = true;
+                            let _ = state.window.set_cursor_grab(CursorGrabMode::None);
+                        } else {
+                            self.would_block = false;
+                            let _ = state.window.set_cursor_grab(CursorGrabMode::Locked);
+                        }
+                    }
+                    _ => state.handle_key(event_loop, code, key_state.is_pressed()),
                 }
-                _ => state.handle_key(event_loop, code, key_state.is_pressed()),
-            },
+            }
             _ => {}
         }
     }
@@ -161,70 +208,196 @@ impl ApplicationHandler<State> for App {
             return;
         };
 
-        match event {
-            DeviceEvent::MouseMotion { delta: (dx, dy) } => {
-                state
-                    .camera_controller
-                    .handle_mouse(dx, dy, self.would_block);
-            }
-            _ => {}
+        if let DeviceEvent::MouseMotion { delta: (dx, dy) } = event {
+            input::push_input_event(InputEvent::MouseMotion { dx, dy });
+            state
+                .camera_controller()
+                .handle_mouse(dx, dy, self.would_block);
         }
     }
 
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if lifecycle::shutdown_requested() {
+            event_loop.exit();
+            return;
+        }
+
         if let Some(state) = &self.state {
             state.window.request_redraw();
         }
     }
-}
 
-pub fn set_position(pos: (f32, f32, f32)) {
-    let lock = GLOBAL_POSITION.get_or_init(|| RwLock::new((0.0, 0.0, 0.0)));
-    if let Ok(mut position) = lock.write() {
-        *position = pos;
-        println!("Position set to: {:?}", pos);
+    fn exiting(&mut self, _event_loop: &ActiveEventLoop) {
+        // Libérer la fenêtre, la surface et toutes les ressources GPU avant de rendre la main
+        self.state = None;
     }
 }
 
-pub fn get_position() -> (f32, f32, f32) {
-    let lock = GLOBAL_POSITION.get_or_init(|| RwLock::new((0.0, 0.0, 0.0)));
-    lock.read().map(|p| *p).unwrap_or((0.0, 0.0, 0.0))
-}
-
-fn run() -> anyhow::Result<()> {
+fn init_logger() {
     #[cfg(not(target_arch = "wasm32"))]
     {
-        env_logger::init();
+        // Un second appel ne doit pas paniquer sur le logger déjà installé
+        let _ = env_logger::try_init();
     }
+}
+
+/// Faire tourner la boucle de rendu jusqu'à sa fin (Échap, fermeture ou `requestShutdown`)
+#[cfg(not(target_arch = "wasm32"))]
+fn run() -> anyhow::Result<()> {
+    init_logger();
+
+    EVENT_LOOP.with(|cell| {
+        let mut slot = cell.borrow_mut();
+        let event_loop = match &mut *slot {
+            Some(event_loop) => event_loop,
+            None => slot.insert(EventLoop::with_user_event().build()?),
+        };
 
-    let event_loop = EventLoop::with_user_event().build()?;
-    let mut app = App::new();
-    event_loop.run_app(&mut app)?;
+        let mut app = App::new();
+        app.commands.extend(command::connect(event_loop.create_proxy())?);
+        let result = event_loop.run_app_on_demand(&mut app);
+        // Les commandes non appliquées restent dans le canal ; un réveil resté dans la file
+        // de winit ne fera que le relire au prochain lancement
+        command::disconnect(app.commands.drain(..).collect())?;
+        Ok(result?)
+    })
+}
 
-    Ok(())
+/// Rendre sans fenêtre l'état envoyé par Java (commandes en attente) dans un PNG
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_to_png(path: &std::path::Path, width: u32, height: u32) -> anyhow::Result<()> {
+    init_logger();
+    let mut renderer = pollster::block_on(HeadlessRenderer::new(width, height))?;
+    renderer.apply_commands(command::pending()?)?;
+    let image = renderer.render_to_image()?;
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", path.display(), e))
 }
 
 #[allow(non_snake_case)]
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_Teste_render<'local>(
-    _env: JNIEnv<'local>,
+    mut env: JNIEnv<'local>,
     _class: JClass<'local>,
 ) {
-    run().unwrap()
+    jni_error::jni_guard(&mut env, (), |env| {
+        lifecycle::run_owned(run, |error| lifecycle::notify_stopped(env, error))
+    })
 }
 
+/// Préparer le rendu : logger, listener de fin (peut être null) et oubli d'un ancien arrêt
 #[allow(non_snake_case)]
 #[unsafe(no_mangle)]
-pub extern "system" fn Java_Teste_updateValue<'local>(
-    _env: JNIEnv<'local>,
+pub extern "system" fn Java_Teste_init<'local>(
+    mut env: JNIEnv<'local>,
     _class: JClass<'local>,
-    x: jdouble,
-    y: jdouble,
-    z: jdouble,
+    listener: JObject<'local>,
 ) {
-    set_position((x as f32, y as f32, z as f32));
-    println!("Position mise à jour avec succès");
+    jni_error::jni_guard(&mut env, (), |env| {
+        init_logger();
+        let listener = if listener.is_null() {
+            None
+        } else {
+            Some(env.new_global_ref(&listener)?)
+        };
+        lifecycle::init(listener)
+    })
 }
 
-// Ré-exporter la fonction JNI pour les chunks
-pub use jni_interface::Java_Teste_updateChunk;
\ No newline at end of file
+/// Demander l'arrêt de la boucle de rendu, sans attendre qu'elle se termine
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_requestShutdown<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) {
+    jni_error::jni_guard(&mut env, (), |_| {
+        lifecycle::request_shutdown();
+        command::wake()
+    })
+}
+
+/// La boucle de rendu tourne-t-elle (y compris pendant son arrêt) ?
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_isRunning<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jboolean {
+    jni_error::jni_guard(&mut env, 0, |_| Ok(lifecycle::is_running() as jboolean))
+}
+
+/// Vider les entrées du joueur : `double[]` de triplets (type, a, b), voir `InputEvents`
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_pollInputEvents<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> jdoubleArray {
+    jni_error::jni_guard(&mut env, std::ptr::null_mut(), |env| {
+        let packed = input::drain_packed()?;
+        let array = env.new_double_array(packed.len() as i32)?;
+        env.set_double_array_region(&array, 0, &packed)?;
+        Ok(array.into_raw())
+    })
+}
+
+/// Rendre la scène sans ouvrir de fenêtre et l'écrire en PNG à `path`
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_renderToPng<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    path: JString<'local>,
+) {
+    jni_error::jni_guard(&mut env, (), |env| {
+        anyhow::ensure!(!path.is_null(), "renderToPng path is null");
+        // Pendant la boucle, les commandes vont à la fenêtre et non au rendu hors écran
+        anyhow::ensure!(
+            !lifecycle::is_running(),
+            "renderToPng cannot be used while the render loop is running"
+        );
+        let path: String = env.get_string(&path)?.into();
+        render_to_png(std::path::Path::new(&path), DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    })
+}
+
+/// Capturer la prochaine frame dans un PNG horodaté de `directory` (null : dossier par défaut).
+/// Renvoie le chemin du fichier, écrit en arrière-plan.
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_Teste_takeScreenshot<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    directory: JString<'local>,
+) -> jstring {
+    jni_error::jni_guard(&mut env, std::ptr::null_mut(), |env| {
+        anyhow::ensure!(
+            lifecycle::is_running(),
+            "takeScreenshot needs a running render loop, use renderToPng instead"
+        );
+        let directory = if directory.is_null() {
+            render_backend::screenshot::default_directory()
+        } else {
+            std::path::PathBuf::from(String::from(env.get_string(&directory)?))
+        };
+        let path =
+            render_backend::screenshot::timestamped_path(&directory, std::time::SystemTime::now());
+        // Juste après le lancement, la boucle n'est pas encore branchée sur le canal
+        anyhow::ensure!(
+            command::send_if_connected(RenderCommand::TakeScreenshot(path.clone()))?,
+            "The render loop is not ready for screenshots yet, try again"
+        );
+        Ok(env.new_string(path.to_string_lossy())?.into_raw())
+    })
+}
+
+// Ré-exporter les fonctions JNI des chunks et de la caméra
+pub use jni_interface::{
+    Java_Teste_blockId, Java_Teste_loadBlockRegistry, Java_Teste_setBlock,
+    Java_Teste_setBlockRegistry, Java_Teste_setBlocks, Java_Teste_setCamera,
+    Java_Teste_setCameraFov, Java_Teste_setCameraMode, Java_Teste_setEntityAnimation,
+    Java_Teste_syncEntities, Java_Teste_unloadChunk, Java_Teste_updateChunk,
+    Java_Teste_updateChunkAt, Java_Teste_updateChunkBuffer,
+};
@@ -0,0 +1,191 @@
+use anyhow::Context;
+use jni::JNIEnv;
+use jni::objects::{GlobalRef, JObject, JValue};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use crate::jni_error;
+
+/// État de la boucle de rendu, vu depuis n'importe quel thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RendererStatus {
+    Stopped = 0,
+    Running = 1,
+    /// Arrêt demandé, la boucle termine sa frame et libère le GPU
+    Stopping = 2,
+}
+
+static STATUS: AtomicU8 = AtomicU8::new(RendererStatus::Stopped as u8);
+static SHUTDOWN_REQUESTED: AtomicBool = AtomicBool::new(false);
+static LISTENER: OnceLock<Mutex<Option<GlobalRef>>> = OnceLock::new();
+
+/// Signature Java de `RendererListener.onRendererStopped(RendererException)`
+const LISTENER_METHOD: &str = "onRendererStopped";
+const LISTENER_SIGNATURE: &str = "(LRendererException;)V";
+
+pub fn status() -> RendererStatus {
+    match STATUS.load(Ordering::Acquire) {
+        1 => RendererStatus::Running,
+        2 => RendererStatus::Stopping,
+        _ => RendererStatus::Stopped,
+    }
+}
+
+pub fn is_running() -> bool {
+    status() != RendererStatus::Stopped
+}
+
+/// Demander l'arrêt de la boucle de rendu, pris en compte à la prochaine itération
+pub fn request_shutdown() {
+    SHUTDOWN_REQUESTED.store(true, Ordering::Release);
+    let _ = STATUS.compare_exchange(
+        RendererStatus::Running as u8,
+        RendererStatus::Stopping as u8,
+        Ordering::AcqRel,
+        Ordering::Acquire,
+    );
+}
+
+pub fn shutdown_requested() -> bool {
+    SHUTDOWN_REQUESTED.load(Ordering::Acquire)
+}
+
+/// Préparer un nouveau lancement : oublier un ancien arrêt demandé et installer le listener
+pub fn init(listener: Option<GlobalRef>) -> anyhow::Result<()> {
+    anyhow::ensure!(!is_running(), "Cannot init while the renderer is running");
+
+    SHUTDOWN_REQUESTED.store(false, Ordering::Release);
+    *get_listener()
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Failed to acquire lock on renderer listener"))? = listener;
+    Ok(())
+}
+
+fn get_listener() -> &'static Mutex<Option<GlobalRef>> {
+    LISTENER.get_or_init(|| Mutex::new(None))
+}
+
+/// Marque la boucle comme lancée tant qu'il est vivant, même si elle panique
+pub struct RunningGuard(());
+
+/// Passer à `Running`, erreur si une boucle tourne déjà
+pub fn begin() -> anyhow::Result<RunningGuard> {
+    STATUS
+        .compare_exchange(
+            RendererStatus::Stopped as u8,
+            RendererStatus::Running as u8,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        )
+        .map_err(|_| anyhow::anyhow!("Renderer is already running"))?;
+
+    // Un arrêt demandé avant le démarrage effectif est honoré dès la première frame
+    if shutdown_requested() {
+        request_shutdown();
+    }
+    Ok(RunningGuard(()))
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        SHUTDOWN_REQUESTED.store(false, Ordering::Release);
+        STATUS.store(RendererStatus::Stopped as u8, Ordering::Release);
+    }
+}
+
+/// Lancer `render_loop` si aucune boucle ne tourne, puis passer son résultat à `on_stopped`.
+/// Si une boucle tourne déjà, l'erreur est renvoyée sans appeler `on_stopped` : ce n'est pas
+/// cet appel qui s'arrête.
+pub fn run_owned(
+    render_loop: impl FnOnce() -> anyhow::Result<()>,
+    on_stopped: impl FnOnce(Option<&anyhow::Error>) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let running = begin()?;
+    let result = render_loop();
+    drop(running);
+    on_stopped(result.as_ref().err())?;
+    result
+}
+
+/// Prévenir le listener Java de la fin de la boucle, avec l'erreur éventuelle
+pub fn notify_stopped(env: &mut JNIEnv, error: Option<&anyhow::Error>) -> anyhow::Result<()> {
+    let listener = get_listener()
+        .lock()
+        .map_err(|_| anyhow::anyhow!("Failed to acquire lock on renderer listener"))?
+        .clone();
+    let Some(listener) = listener else {
+        return Ok(());
+    };
+
+    let exception = match error {
+        Some(error) => jni_error::new_renderer_exception(env, error, JObject::null())
+            .context("Failed to create RendererException for listener")?,
+        None => JObject::null(),
+    };
+    env.call_method(
+        listener.as_obj(),
+        LISTENER_METHOD,
+        LISTENER_SIGNATURE,
+        &[JValue::Object(&exception)],
+    )
+    .context("RendererListener.onRendererStopped failed")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Les tests partagent l'état global de la boucle
+    static LIFECYCLE: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn shutdown_moves_running_to_stopping_then_stopped() {
+        let _lock = LIFECYCLE.lock().unwrap_or_else(|e| e.into_inner());
+        let guard = begin().unwrap();
+        assert_eq!(status(), RendererStatus::Running);
+        assert!(begin().is_err());
+
+        request_shutdown();
+        assert_eq!(status(), RendererStatus::Stopping);
+        assert!(shutdown_requested());
+        assert!(init(None).is_err());
+
+        drop(guard);
+        assert_eq!(status(), RendererStatus::Stopped);
+        assert!(!shutdown_requested());
+        assert!(init(None).is_ok());
+    }
+
+    #[test]
+    fn a_second_render_while_running_fails_without_notifying() {
+        let _lock = LIFECYCLE.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stopped = Vec::new();
+        let mut second_notified = false;
+
+        let result = run_owned(
+            || {
+                let second = run_owned(
+                    || panic!("the second loop must not run"),
+                    |_| {
+                        second_notified = true;
+                        Ok(())
+                    },
+                );
+                assert!(second.is_err());
+                assert_eq!(status(), RendererStatus::Running);
+                Ok(())
+            },
+            |error| {
+                stopped.push(error.is_some());
+                Ok(())
+            },
+        );
+
+        assert!(result.is_ok());
+        assert!(!second_notified);
+        assert_eq!(stopped, vec![false]);
+        assert_eq!(status(), RendererStatus::Stopped);
+    }
+}
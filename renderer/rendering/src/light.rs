@@ -0,0 +1,73 @@
+use cgmath::{InnerSpace, Vector3};
+
+/// Soleil : lumière directionnelle et lumière ambiante, appliquées à toute la scène
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DirectionalLight {
+    /// Sens dans lequel la lumière se propage (du soleil vers le sol)
+    pub direction: Vector3<f32>,
+    pub color: [f32; 3],
+    /// Éclairage des faces à l'ombre, pour que le relief reste lisible
+    pub ambient: [f32; 3],
+}
+
+impl Default for DirectionalLight {
+    /// Soleil de milieu de matinée : les trois axes reçoivent des éclairages différents
+    fn default() -> Self {
+        Self {
+            direction: Vector3::new(-0.4, -1.0, -0.3),
+            color: [1.0, 0.97, 0.9],
+            ambient: [0.35, 0.37, 0.42],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct LightUniform {
+    /// Direction normalisée vers le soleil (opposée à `DirectionalLight::direction`)
+    to_light: [f32; 3],
+    _padding0: f32,
+    color: [f32; 3],
+    _padding1: f32,
+    ambient: [f32; 3],
+    _padding2: f32,
+}
+
+impl LightUniform {
+    pub fn new(light: &DirectionalLight) -> Self {
+        // Une direction nulle éclaire d'en haut plutôt que de produire des NaN
+        let to_light = if light.direction.magnitude2() > 0.0 {
+            -light.direction.normalize()
+        } else {
+            Vector3::unit_y()
+        };
+        Self {
+            to_light: to_light.into(),
+            _padding0: 0.0,
+            color: light.color,
+            _padding1: 0.0,
+            ambient: light.ambient,
+            _padding2: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_points_towards_the_light() {
+        let uniform = LightUniform::new(&DirectionalLight {
+            direction: Vector3::new(0.0, -2.0, 0.0),
+            ..Default::default()
+        });
+        assert_eq!(uniform.to_light, [0.0, 1.0, 0.0]);
+
+        let degenerate = LightUniform::new(&DirectionalLight {
+            direction: Vector3::new(0.0, 0.0, 0.0),
+            ..Default::default()
+        });
+        assert_eq!(degenerate.to_light, [0.0, 1.0, 0.0]);
+    }
+}
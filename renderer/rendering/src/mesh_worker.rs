@@ -0,0 +1,158 @@
+use crate::chunk_mesher::{ChunkMesh, ChunkMeshData, ChunkNeighbourhood, MeshingMode};
+use crate::chunk_storage::ChunkStorage;
+use crate::world::ChunkPos;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Copie des blocs d'un chunk et de ses voisins, envoyée à un thread de meshing
+pub struct MeshJob {
+    pub pos: ChunkPos,
+    /// Version du chunk au moment de la copie, pour ignorer les résultats périmés
+    pub generation: u64,
+    pub mode: MeshingMode,
+    pub center: Arc<ChunkStorage>,
+    /// Voisins chargés, indexés par `Face::index`
+    pub neighbours: [Option<Arc<ChunkStorage>>; 6],
+}
+
+impl MeshJob {
+    fn run(self) -> MeshResult {
+        let mut neighbourhood = ChunkNeighbourhood::isolated(&self.center);
+        for (slot, neighbour) in neighbourhood.neighbours.iter_mut().zip(&self.neighbours) {
+            *slot = neighbour.as_deref();
+        }
+
+        let mesh = ChunkMesh::build_with_neighbours(&neighbourhood, self.mode).to_mesh_data();
+        MeshResult {
+            pos: self.pos,
+            generation: self.generation,
+            mesh,
+        }
+    }
+}
+
+/// Mesh CPU terminé, prêt à être envoyé au GPU par le thread de rendu
+pub struct MeshResult {
+    pub pos: ChunkPos,
+    pub generation: u64,
+    pub mesh: ChunkMeshData,
+}
+
+/// Pool de threads qui génèrent les meshes de chunks hors du thread de rendu
+pub struct MeshWorkerPool {
+    jobs: Option<Sender<MeshJob>>,
+    results: Receiver<MeshResult>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl MeshWorkerPool {
+    pub fn new(threads: usize) -> Self {
+        let (job_sender, job_receiver) = mpsc::channel::<MeshJob>();
+        let (result_sender, results) = mpsc::channel();
+        let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+        let workers = (0..threads.max(1))
+            .map(|i| {
+                let jobs = Arc::clone(&job_receiver);
+                let results = result_sender.clone();
+                std::thread::Builder::new()
+                    .name(format!("mesh-worker-{}", i))
+                    .spawn(move || {
+                        loop {
+                            // Le verrou n'est tenu que le temps de recevoir un job
+                            let job = match jobs.lock() {
+                                Ok(jobs) => jobs.recv(),
+                                Err(_) => return,
+                            };
+                            let Ok(job) = job else {
+                                return;
+                            };
+                            if results.send(job.run()).is_err() {
+                                return;
+                            }
+                        }
+                    })
+                    .expect("Failed to spawn mesh worker thread")
+            })
+            .collect();
+
+        Self {
+            jobs: Some(job_sender),
+            results,
+            workers,
+        }
+    }
+
+    /// Nombre de threads par défaut : les cœurs disponibles moins le thread de rendu
+    pub fn default_thread_count() -> usize {
+        std::thread::available_parallelism()
+            .map(|n| n.get().saturating_sub(1))
+            .unwrap_or(1)
+            .clamp(1, 4)
+    }
+
+    pub fn submit(&self, job: MeshJob) {
+        if let Some(jobs) = &self.jobs
+            && jobs.send(job).is_err()
+        {
+            log::error!("Mesh workers stopped, dropping mesh job");
+        }
+    }
+
+    /// Récupérer les meshes terminés sans bloquer
+    pub fn try_recv(&self) -> impl Iterator<Item = MeshResult> + '_ {
+        self.results.try_iter()
+    }
+
+    /// Attendre le prochain mesh terminé, au plus `timeout`
+    pub fn recv_timeout(&self, timeout: std::time::Duration) -> Option<MeshResult> {
+        self.results.recv_timeout(timeout).ok()
+    }
+}
+
+impl Drop for MeshWorkerPool {
+    fn drop(&mut self) {
+        // Fermer le canal des jobs pour que les threads sortent de leur boucle
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn workers_mesh_submitted_chunks() {
+        let pool = MeshWorkerPool::new(2);
+
+        for generation in 0..3 {
+            let mut data = ChunkStorage::empty();
+            data.set(1, 1, 1, 1);
+            pool.submit(MeshJob {
+                pos: ChunkPos::new(generation as i32, 0, 0),
+                generation,
+                mode: MeshingMode::Culled,
+                center: Arc::new(data),
+                neighbours: Default::default(),
+            });
+        }
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let mut results = Vec::new();
+        while results.len() < 3 && Instant::now() < deadline {
+            results.extend(pool.try_recv());
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        assert_eq!(results.len(), 3);
+        for result in results {
+            assert_eq!(result.pos.x as u64, result.generation);
+            assert_eq!(result.mesh.quad_count(), 6);
+        }
+    }
+}
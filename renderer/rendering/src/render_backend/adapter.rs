@@ -0,0 +1,214 @@
+/// Choix du GPU : backends autorisés et recours à l'adaptateur logiciel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdapterConfig {
+    pub backends: wgpu::Backends,
+    /// N'accepter qu'un adaptateur logiciel (llvmpipe, WARP...), pour les machines sans GPU
+    pub force_fallback_adapter: bool,
+}
+
+impl Default for AdapterConfig {
+    fn default() -> Self {
+        Self {
+            backends: wgpu::Backends::all(),
+            force_fallback_adapter: false,
+        }
+    }
+}
+
+impl AdapterConfig {
+    /// Lire `MINI_GAME_BACKEND` (`vulkan`, `gl`, `dx12`, `metal` ou `all`, séparés par des virgules)
+    /// et `MINI_GAME_FORCE_FALLBACK_ADAPTER` (`1` ou `true`)
+    pub fn from_env() -> anyhow::Result<Self> {
+        Self::from_values(
+            std::env::var("MINI_GAME_BACKEND").ok().as_deref(),
+            std::env::var("MINI_GAME_FORCE_FALLBACK_ADAPTER").ok().as_deref(),
+        )
+    }
+
+    fn from_values(backend: Option<&str>, fallback: Option<&str>) -> anyhow::Result<Self> {
+        let mut config = Self::default();
+        if let Some(backend) = backend.filter(|value| !value.trim().is_empty()) {
+            config.backends = parse_backends(backend)?;
+        }
+        if let Some(fallback) = fallback {
+            config.force_fallback_adapter = match fallback.trim().to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" => true,
+                "" | "0" | "false" | "no" => false,
+                other => anyhow::bail!(
+                    "Invalid MINI_GAME_FORCE_FALLBACK_ADAPTER value {:?}, expected true or false",
+                    other
+                ),
+            };
+        }
+        Ok(config)
+    }
+
+    pub fn create_instance(&self) -> wgpu::Instance {
+        wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: self.backends,
+            ..Default::default()
+        })
+    }
+
+    /// Trouver un adaptateur, compatible avec `surface` s'il y en a une.
+    ///
+    /// L'erreur liste les adaptateurs présents sur la machine, tous backends confondus.
+    pub async fn request_adapter(
+        &self,
+        instance: &wgpu::Instance,
+        surface: Option<&wgpu::Surface<'_>>,
+    ) -> anyhow::Result<wgpu::Adapter> {
+        let request = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: Default::default(),
+                force_fallback_adapter: self.force_fallback_adapter,
+                compatible_surface: surface,
+            })
+            .await;
+
+        match request {
+            Ok(adapter) => {
+                let info = adapter.get_info();
+                log::info!("Using adapter {} ({:?}, {:?})", info.name, info.backend, info.device_type);
+                Ok(adapter)
+            }
+            Err(err) => anyhow::bail!(
+                "No adapter matches backends {:?} (force_fallback_adapter: {}): {}. Available adapters: {}",
+                self.backends,
+                self.force_fallback_adapter,
+                err,
+                available_adapters()
+            ),
+        }
+    }
+}
+
+/// Device et file de commandes avec les limites par défaut, erreur si l'adaptateur
+/// ne peut pas exécuter le shader
+pub async fn request_device(adapter: &wgpu::Adapter) -> anyhow::Result<(wgpu::Device, wgpu::Queue)> {
+    check_vertex_storage(
+        &adapter.get_info(),
+        &adapter.get_downlevel_capabilities(),
+        &adapter.limits(),
+    )?;
+    let (device, queue) = adapter
+        .request_device(&wgpu::DeviceDescriptor {
+            label: Some("Device"),
+            required_features: wgpu::Features::empty(),
+            experimental_features: wgpu::ExperimentalFeatures::disabled(),
+            required_limits: wgpu::Limits::default(),
+            trace: wgpu::Trace::Off,
+            ..Default::default()
+        })
+        .await?;
+    Ok((device, queue))
+}
+
+/// Le skinning lit les matrices d'articulations dans un buffer de stockage depuis le vertex
+/// shader, ce que beaucoup d'adaptateurs GL/GLES ne permettent pas
+fn check_vertex_storage(
+    info: &wgpu::AdapterInfo,
+    downlevel: &wgpu::DownlevelCapabilities,
+    limits: &wgpu::Limits,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        downlevel.flags.contains(wgpu::DownlevelFlags::VERTEX_STORAGE)
+            && limits.max_storage_buffers_per_shader_stage > 0,
+        "Adapter {} ({:?}) cannot read storage buffers in vertex shaders, which skinning needs; \
+         try another backend with MINI_GAME_BACKEND (e.g. vulkan)",
+        info.name,
+        info.backend
+    );
+    Ok(())
+}
+
+/// Convertir une liste comme `vulkan,gl` en backends wgpu
+pub fn parse_backends(value: &str) -> anyhow::Result<wgpu::Backends> {
+    value
+        .split(',')
+        .map(|name| match name.trim().to_ascii_lowercase().as_str() {
+            "vulkan" | "vk" => Ok(wgpu::Backends::VULKAN),
+            "gl" | "gles" | "opengl" => Ok(wgpu::Backends::GL),
+            "dx12" | "d3d12" => Ok(wgpu::Backends::DX12),
+            "metal" | "mtl" => Ok(wgpu::Backends::METAL),
+            "all" => Ok(wgpu::Backends::all()),
+            other => anyhow::bail!(
+                "Unknown backend {:?}, expected vulkan, gl, dx12, metal or all",
+                other
+            ),
+        })
+        .try_fold(wgpu::Backends::empty(), |backends, backend| Ok(backends | backend?))
+}
+
+/// "nom (backend, type)" de chaque adaptateur visible, ou "none"
+fn available_adapters() -> String {
+    let instance = AdapterConfig::default().create_instance();
+    let adapters: Vec<String> = instance
+        .enumerate_adapters(wgpu::Backends::all())
+        .iter()
+        .map(|adapter| {
+            let info = adapter.get_info();
+            format!("{} ({:?}, {:?})", info.name, info.backend, info.device_type)
+        })
+        .collect();
+    if adapters.is_empty() {
+        "none".to_string()
+    } else {
+        adapters.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backends_are_parsed_from_a_list() {
+        assert_eq!(parse_backends("vulkan").unwrap(), wgpu::Backends::VULKAN);
+        assert_eq!(
+            parse_backends(" Vulkan , gl").unwrap(),
+            wgpu::Backends::VULKAN | wgpu::Backends::GL
+        );
+        assert_eq!(parse_backends("all").unwrap(), wgpu::Backends::all());
+        let err = parse_backends("vulkan,directx").unwrap_err();
+        assert!(err.to_string().contains("\"directx\""));
+    }
+
+    #[test]
+    fn config_defaults_to_every_backend() {
+        assert_eq!(AdapterConfig::from_values(None, None).unwrap(), AdapterConfig::default());
+        assert_eq!(AdapterConfig::from_values(Some(""), Some("")).unwrap(), AdapterConfig::default());
+
+        let config = AdapterConfig::from_values(Some("gl"), Some("TRUE")).unwrap();
+        assert_eq!(config.backends, wgpu::Backends::GL);
+        assert!(config.force_fallback_adapter);
+        assert!(AdapterConfig::from_values(None, Some("maybe")).is_err());
+    }
+
+    #[test]
+    fn adapters_without_vertex_storage_are_rejected() {
+        let info = wgpu::AdapterInfo {
+            name: "Old GLES".to_string(),
+            vendor: 0,
+            device: 0,
+            device_type: wgpu::DeviceType::IntegratedGpu,
+            driver: String::new(),
+            driver_info: String::new(),
+            backend: wgpu::Backend::Gl,
+        };
+        let full = wgpu::DownlevelCapabilities::default();
+        let limits = wgpu::Limits::default();
+        assert!(check_vertex_storage(&info, &full, &limits).is_ok());
+
+        let mut downlevel = full.clone();
+        downlevel.flags.remove(wgpu::DownlevelFlags::VERTEX_STORAGE);
+        let err = check_vertex_storage(&info, &downlevel, &limits).unwrap_err();
+        assert!(err.to_string().contains("Old GLES (Gl)"), "{}", err);
+
+        let no_storage = wgpu::Limits {
+            max_storage_buffers_per_shader_stage: 0,
+            ..wgpu::Limits::default()
+        };
+        assert!(check_vertex_storage(&info, &full, &no_storage).is_err());
+    }
+}
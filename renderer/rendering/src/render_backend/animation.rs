@@ -0,0 +1,369 @@
+use anyhow::Context;
+use cgmath::{InnerSpace, Matrix4, Quaternion, SquareMatrix, Vector3, Vector4, VectorSpace};
+use std::collections::HashMap;
+
+/// Matrice d'articulation telle qu'envoyée au shader
+pub type JointMatrix = [[f32; 4]; 4];
+
+/// Transformation locale d'un nœud glTF (translation, rotation, échelle)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodePose {
+    pub translation: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+    pub scale: Vector3<f32>,
+}
+
+impl NodePose {
+    fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.translation)
+            * Matrix4::from(self.rotation)
+            * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+    }
+}
+
+/// Articulation d'un skin : un nœud et l'inverse de sa matrice au moment de l'attache
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Joint {
+    node: usize,
+    inverse_bind: Matrix4<f32>,
+}
+
+/// Hiérarchie des nœuds d'un fichier glTF et articulations de tous ses skins, mises bout à bout
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    rest: Vec<NodePose>,
+    parents: Vec<Option<usize>>,
+    /// Nœuds triés parents d'abord
+    order: Vec<usize>,
+    joints: Vec<Joint>,
+    /// Première articulation de chaque skin glTF dans `joints`
+    skin_offsets: HashMap<usize, usize>,
+}
+
+impl Skeleton {
+    pub fn from_document(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+    ) -> anyhow::Result<Self> {
+        let rest: Vec<NodePose> = document
+            .nodes()
+            .map(|node| {
+                let (translation, rotation, scale) = node.transform().decomposed();
+                NodePose {
+                    translation: translation.into(),
+                    rotation: quaternion(rotation),
+                    scale: scale.into(),
+                }
+            })
+            .collect();
+
+        let mut parents = vec![None; rest.len()];
+        for node in document.nodes() {
+            for child in node.children() {
+                parents[child.index()] = Some(node.index());
+            }
+        }
+
+        let mut order = Vec::with_capacity(rest.len());
+        let mut stack: Vec<gltf::Node> = document
+            .nodes()
+            .filter(|node| parents[node.index()].is_none())
+            .collect();
+        while let Some(node) = stack.pop() {
+            order.push(node.index());
+            stack.extend(node.children());
+        }
+
+        let mut skeleton = Self {
+            rest,
+            parents,
+            order,
+            ..Default::default()
+        };
+        for skin in document.skins() {
+            skeleton.skin_offsets.insert(skin.index(), skeleton.joints.len());
+            let reader = skin.reader(|buffer| buffers.get(buffer.index()).map(|data| &data.0[..]));
+            // Sans matrices fournies, ce sont des identités
+            let mut inverse_binds = reader
+                .read_inverse_bind_matrices()
+                .map(|matrices| matrices.map(Matrix4::from).collect::<Vec<_>>())
+                .unwrap_or_default()
+                .into_iter();
+            for node in skin.joints() {
+                skeleton.joints.push(Joint {
+                    node: node.index(),
+                    inverse_bind: inverse_binds.next().unwrap_or_else(Matrix4::identity),
+                });
+            }
+        }
+        Ok(skeleton)
+    }
+
+    /// Nombre d'articulations, tous skins confondus (0 pour un modèle rigide)
+    pub fn joint_count(&self) -> usize {
+        self.joints.len()
+    }
+
+    /// Décalage à ajouter aux indices `JOINTS_0` d'une primitive attachée à ce skin
+    pub fn skin_offset(&self, skin: usize) -> Option<usize> {
+        self.skin_offsets.get(&skin).copied()
+    }
+
+    /// Matrices des articulations, au repos ou à l'instant `time` d'une animation
+    pub fn joint_matrices(&self, animation: Option<(&AnimationClip, f32)>, out: &mut Vec<JointMatrix>) {
+        let mut poses = self.rest.clone();
+        if let Some((clip, time)) = animation {
+            clip.apply(time, &mut poses);
+        }
+
+        let mut globals = vec![Matrix4::identity(); poses.len()];
+        for &node in &self.order {
+            let local = poses[node].matrix();
+            globals[node] = match self.parents[node] {
+                Some(parent) => globals[parent] * local,
+                None => local,
+            };
+        }
+
+        out.extend(self.joints.iter().map(|joint| -> JointMatrix {
+            (globals[joint.node] * joint.inverse_bind).into()
+        }));
+    }
+}
+
+/// Interpolation entre deux images clés, comme dans glTF
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    Step,
+    Linear,
+    /// Chaque image clé stocke (tangente entrante, valeur, tangente sortante)
+    CubicSpline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Property {
+    Translation,
+    Rotation,
+    Scale,
+}
+
+/// Courbe d'une propriété d'un nœud ; rotations en (x, y, z, w), vecteurs avec w = 0
+#[derive(Debug, Clone)]
+struct Channel {
+    node: usize,
+    property: Property,
+    interpolation: Interpolation,
+    times: Vec<f32>,
+    values: Vec<Vector4<f32>>,
+}
+
+impl Channel {
+    fn sample(&self, time: f32) -> Vector4<f32> {
+        let is_rotation = self.property == Property::Rotation;
+        let value = sample(&self.times, &self.values, self.interpolation, time, is_rotation);
+        if is_rotation {
+            value.normalize()
+        } else {
+            value
+        }
+    }
+}
+
+/// Valeur d'une courbe à l'instant `time`, bornée à la première et à la dernière image clé
+fn sample(
+    times: &[f32],
+    values: &[Vector4<f32>],
+    interpolation: Interpolation,
+    time: f32,
+    is_rotation: bool,
+) -> Vector4<f32> {
+    let value = |key: usize| match interpolation {
+        Interpolation::CubicSpline => values[key * 3 + 1],
+        _ => values[key],
+    };
+
+    let next = times.partition_point(|&t| t <= time);
+    if next == 0 {
+        return value(0);
+    }
+    if next == times.len() {
+        return value(times.len() - 1);
+    }
+
+    let key = next - 1;
+    let delta = times[next] - times[key];
+    let s = (time - times[key]) / delta;
+    match interpolation {
+        Interpolation::Step => value(key),
+        Interpolation::Linear if is_rotation => {
+            let rotation = quaternion(value(key).into()).slerp(quaternion(value(next).into()), s);
+            Vector4::new(rotation.v.x, rotation.v.y, rotation.v.z, rotation.s)
+        }
+        Interpolation::Linear => value(key).lerp(value(next), s),
+        Interpolation::CubicSpline => {
+            // Spline de Hermite : tangente sortante de `key`, entrante de `next`
+            let out_tangent = values[key * 3 + 2] * delta;
+            let in_tangent = values[next * 3] * delta;
+            let (s2, s3) = (s * s, s * s * s);
+            value(key) * (2.0 * s3 - 3.0 * s2 + 1.0)
+                + out_tangent * (s3 - 2.0 * s2 + s)
+                + value(next) * (-2.0 * s3 + 3.0 * s2)
+                + in_tangent * (s3 - s2)
+        }
+    }
+}
+
+/// Animation glTF : courbes de translation, rotation et échelle des nœuds
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    pub name: String,
+    /// Date de la dernière image clé, en secondes
+    pub duration: f32,
+    channels: Vec<Channel>,
+}
+
+impl AnimationClip {
+    pub fn read(
+        animation: &gltf::Animation,
+        buffers: &[gltf::buffer::Data],
+    ) -> anyhow::Result<Self> {
+        use gltf::animation::util::ReadOutputs;
+
+        let name = animation
+            .name()
+            .map_or_else(|| format!("animation_{}", animation.index()), str::to_string);
+
+        let mut channels = Vec::new();
+        for channel in animation.channels() {
+            let reader = channel.reader(|buffer| buffers.get(buffer.index()).map(|data| &data.0[..]));
+            let times: Vec<f32> = reader
+                .read_inputs()
+                .with_context(|| format!("Channel {} of {} has no inputs", channel.index(), name))?
+                .collect();
+            let outputs = reader
+                .read_outputs()
+                .with_context(|| format!("Channel {} of {} has no outputs", channel.index(), name))?;
+
+            let (property, values): (Property, Vec<Vector4<f32>>) = match outputs {
+                ReadOutputs::Translations(values) => {
+                    (Property::Translation, values.map(|[x, y, z]| Vector4::new(x, y, z, 0.0)).collect())
+                }
+                ReadOutputs::Rotations(values) => {
+                    (Property::Rotation, values.into_f32().map(Vector4::from).collect())
+                }
+                ReadOutputs::Scales(values) => {
+                    (Property::Scale, values.map(|[x, y, z]| Vector4::new(x, y, z, 0.0)).collect())
+                }
+                ReadOutputs::MorphTargetWeights(_) => {
+                    log::warn!("Skipping morph target channel in animation {}", name);
+                    continue;
+                }
+            };
+
+            let interpolation = match channel.sampler().interpolation() {
+                gltf::animation::Interpolation::Step => Interpolation::Step,
+                gltf::animation::Interpolation::Linear => Interpolation::Linear,
+                gltf::animation::Interpolation::CubicSpline => Interpolation::CubicSpline,
+            };
+            let per_key = if interpolation == Interpolation::CubicSpline { 3 } else { 1 };
+            anyhow::ensure!(
+                !times.is_empty() && values.len() == times.len() * per_key,
+                "Channel {} of {} has {} keyframes but {} values",
+                channel.index(),
+                name,
+                times.len(),
+                values.len()
+            );
+
+            channels.push(Channel {
+                node: channel.target().node().index(),
+                property,
+                interpolation,
+                times,
+                values,
+            });
+        }
+
+        let duration = channels
+            .iter()
+            .filter_map(|channel| channel.times.last().copied())
+            .fold(0.0, f32::max);
+        Ok(Self { name, duration, channels })
+    }
+
+    /// Remplacer dans `poses` les propriétés animées par leur valeur à `time`
+    fn apply(&self, time: f32, poses: &mut [NodePose]) {
+        for channel in &self.channels {
+            let Some(pose) = poses.get_mut(channel.node) else {
+                continue;
+            };
+            let value = channel.sample(time);
+            match channel.property {
+                Property::Translation => pose.translation = value.truncate(),
+                Property::Rotation => pose.rotation = quaternion(value.into()),
+                Property::Scale => pose.scale = value.truncate(),
+            }
+        }
+    }
+}
+
+/// Quaternion glTF (x, y, z, w)
+fn quaternion([x, y, z, w]: [f32; 4]) -> Quaternion<f32> {
+    Quaternion::new(w, x, y, z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scalar(values: &[f32]) -> Vec<Vector4<f32>> {
+        values.iter().map(|&v| Vector4::new(v, 0.0, 0.0, 0.0)).collect()
+    }
+
+    #[test]
+    fn step_and_linear_sampling() {
+        let times = [0.0, 1.0, 3.0];
+        let values = scalar(&[0.0, 10.0, 30.0]);
+        let at = |interpolation, time| sample(&times, &values, interpolation, time, false).x;
+
+        assert_eq!(at(Interpolation::Linear, -1.0), 0.0);
+        assert_eq!(at(Interpolation::Linear, 0.5), 5.0);
+        assert_eq!(at(Interpolation::Linear, 2.0), 20.0);
+        assert_eq!(at(Interpolation::Linear, 5.0), 30.0);
+        assert_eq!(at(Interpolation::Step, 0.99), 0.0);
+        assert_eq!(at(Interpolation::Step, 1.0), 10.0);
+    }
+
+    #[test]
+    fn cubic_spline_follows_tangents() {
+        // Tangentes nulles : courbe en S entre 0 et 1, qui passe par 0.5 au milieu
+        let times = [0.0, 1.0];
+        let flat = scalar(&[0.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        let at = |values: &[Vector4<f32>], time| {
+            sample(&times, values, Interpolation::CubicSpline, time, false).x
+        };
+        assert_eq!(at(&flat, 0.5), 0.5);
+        assert!(at(&flat, 0.25) < 0.25);
+
+        // Tangentes de pente 1 : la spline redevient une droite
+        let straight = scalar(&[1.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+        assert!((at(&straight, 0.25) - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn linear_rotation_uses_slerp() {
+        let half_turn = cgmath::Quaternion::from(cgmath::Euler::new(
+            cgmath::Deg(0.0),
+            cgmath::Deg(90.0),
+            cgmath::Deg(0.0),
+        ));
+        let values = [
+            Vector4::new(0.0, 0.0, 0.0, 1.0),
+            Vector4::new(half_turn.v.x, half_turn.v.y, half_turn.v.z, half_turn.s),
+        ];
+        let mid = sample(&[0.0, 1.0], &values, Interpolation::Linear, 0.5, true);
+        // 45° autour de Y
+        assert!((mid.magnitude() - 1.0).abs() < 1e-6);
+        assert!((mid.y - (std::f32::consts::FRAC_PI_8).sin()).abs() < 1e-6);
+    }
+}
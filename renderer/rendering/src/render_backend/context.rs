@@ -1,7 +1,8 @@
 use std::sync::Arc;
-use wgpu;
 use winit::window::Window;
 
+use crate::render_backend::adapter::{request_device, AdapterConfig};
+
 /// Encapsule le contexte WGPU (device, queue, surface)
 pub struct WgpuContext {
     pub device: wgpu::Device,
@@ -15,35 +16,20 @@ impl WgpuContext {
     pub async fn new(window: Arc<Window>) -> anyhow::Result<Self> {
         let size = window.inner_size();
 
-        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::DX12,
-            ..Default::default()
-        });
+        let adapter_config = AdapterConfig::from_env()?;
+        let instance = adapter_config.create_instance();
 
         let surface = instance.create_surface(window.clone())?;
 
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: Default::default(),
-                force_fallback_adapter: false,
-                compatible_surface: None,
-            })
-            .await?;
-
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: Some("Device"),
-                    required_features: wgpu::Features::empty(),
-                    experimental_features: wgpu::ExperimentalFeatures::disabled(),
-                    required_limits: wgpu::Limits::default(),
-                    trace: wgpu::Trace::Off,
-                    ..Default::default()
-                },
-            )
-            .await?;
+        let adapter = adapter_config.request_adapter(&instance, Some(&surface)).await?;
+        let (device, queue) = request_device(&adapter).await?;
 
         let surface_caps = surface.get_capabilities(&adapter);
+        anyhow::ensure!(
+            !surface_caps.formats.is_empty(),
+            "Adapter {} cannot present to this window",
+            adapter.get_info().name
+        );
         let surface_format = surface_caps
             .formats
             .iter()
@@ -51,8 +37,12 @@ impl WgpuContext {
             .find(|f| f.is_srgb())
             .unwrap_or(surface_caps.formats[0]);
 
+        // Les captures d'écran copient la swapchain, quand la surface le permet
+        let usage = wgpu::TextureUsages::RENDER_ATTACHMENT
+            | (surface_caps.usages & wgpu::TextureUsages::COPY_SRC);
+
         let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage,
             format: surface_format,
             width: size.width.max(1),
             height: size.height.max(1),
@@ -0,0 +1,218 @@
+//! Tests de rendu : des scènes fixes rendues sans fenêtre sur un adaptateur logiciel sont
+//! comparées aux PNG de référence de `golden/`.
+//!
+//! `MINI_GAME_UPDATE_GOLDEN=1 cargo test golden` réécrit les références. En cas d'échec,
+//! l'image obtenue et une image des différences sont écrites dans `target/golden/`.
+//!
+//! Sans adaptateur logiciel, les tests sont sautés, sauf si `CI` ou
+//! `MINI_GAME_REQUIRE_GOLDEN` est défini : ils échouent alors.
+//!
+//! `render_scene` et `camera` servent aussi aux tests de rendu des autres modules.
+
+use std::path::{Path, PathBuf};
+
+use cgmath::{Quaternion, Vector3};
+use image::{Rgba, RgbaImage};
+
+use crate::chunk_storage::ChunkStorage;
+use crate::command::RenderCommand;
+use crate::entity_renderer::EntityState;
+use crate::render_backend::adapter::AdapterConfig;
+use crate::render_backend::instance::Instance;
+use crate::render_backend::HeadlessRenderer;
+use crate::world::ChunkPos;
+
+pub(crate) const WIDTH: u32 = 160;
+pub(crate) const HEIGHT: u32 = 120;
+
+/// Écart maximal par canal, les rasteriseurs logiciels n'arrondissent pas tous pareil
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Part des pixels qui peuvent dépasser la tolérance (bords des triangles)
+const MAX_MISMATCH_RATIO: f64 = 0.005;
+
+/// Pixels qui diffèrent de plus que la tolérance, et l'image qui les montre en rouge
+struct Comparison {
+    mismatched: usize,
+    max_difference: u8,
+    diff: RgbaImage,
+}
+
+fn compare(actual: &RgbaImage, expected: &RgbaImage, tolerance: u8) -> Comparison {
+    let mut diff = RgbaImage::new(expected.width(), expected.height());
+    let mut mismatched = 0;
+    let mut max_difference = 0;
+    for (x, y, expected_pixel) in expected.enumerate_pixels() {
+        let actual_pixel = actual.get_pixel(x, y);
+        let difference = expected_pixel
+            .0
+            .iter()
+            .zip(actual_pixel.0)
+            .map(|(&a, b)| a.abs_diff(b))
+            .max()
+            .unwrap_or(0);
+        max_difference = max_difference.max(difference);
+
+        let pixel = if difference > tolerance {
+            mismatched += 1;
+            Rgba([255, 0, 0, 255])
+        } else {
+            // Référence assombrie, pour situer les différences
+            let [r, g, b, _] = expected_pixel.0;
+            let luma = ((r as u32 + g as u32 + b as u32) / 9) as u8;
+            Rgba([luma, luma, luma, 255])
+        };
+        diff.put_pixel(x, y, pixel);
+    }
+    Comparison {
+        mismatched,
+        max_difference,
+        diff,
+    }
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("golden")
+        .join(format!("{}.png", name))
+}
+
+fn output_path(name: &str, suffix: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("golden")
+        .join(format!("{}.{}.png", name, suffix))
+}
+
+/// Les tests doivent-ils échouer plutôt que d'être sautés sans adaptateur logiciel ?
+fn golden_required() -> bool {
+    ["CI", "MINI_GAME_REQUIRE_GOLDEN"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+}
+
+/// Rendre la scène sur l'adaptateur logiciel, `None` si la machine n'en a pas
+pub(crate) fn render_scene(commands: Vec<RenderCommand>) -> Option<RgbaImage> {
+    let config = AdapterConfig {
+        force_fallback_adapter: true,
+        ..Default::default()
+    };
+    let mut renderer =
+        match pollster::block_on(HeadlessRenderer::with_adapter_config(&config, WIDTH, HEIGHT)) {
+            Ok(renderer) => renderer,
+            Err(e) if golden_required() => panic!("No software adapter for golden tests: {}", e),
+            Err(e) => {
+                eprintln!("Skipping golden image test, no software adapter: {}", e);
+                return None;
+            }
+        };
+    renderer.apply_commands(commands).unwrap();
+    Some(renderer.render_to_image().unwrap())
+}
+
+fn assert_matches_golden(name: &str, actual: &RgbaImage) {
+    let path = golden_path(name);
+    if std::env::var("MINI_GAME_UPDATE_GOLDEN").is_ok_and(|value| value == "1") {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        actual.save(&path).unwrap();
+        return;
+    }
+
+    let expected = image::open(&path)
+        .unwrap_or_else(|e| panic!("Missing reference {}: {}", path.display(), e))
+        .to_rgba8();
+    assert_eq!(
+        actual.dimensions(),
+        expected.dimensions(),
+        "{} has the wrong size",
+        name
+    );
+
+    let comparison = compare(actual, &expected, CHANNEL_TOLERANCE);
+    let allowed = (MAX_MISMATCH_RATIO * (WIDTH * HEIGHT) as f64) as usize;
+    if comparison.mismatched > allowed {
+        let actual_path = output_path(name, "actual");
+        let diff_path = output_path(name, "diff");
+        std::fs::create_dir_all(actual_path.parent().unwrap()).unwrap();
+        actual.save(&actual_path).unwrap();
+        comparison.diff.save(&diff_path).unwrap();
+        panic!(
+            "{}: {} pixels differ by up to {} (allowed {}), see {} and {}",
+            name,
+            comparison.mismatched,
+            comparison.max_difference,
+            allowed,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+/// Caméra pilotée uniquement par les commandes, angles en degrés
+pub(crate) fn camera(position: [f32; 3], yaw: f32, pitch: f32) -> Vec<RenderCommand> {
+    vec![
+        RenderCommand::SetCameraMode(crate::camera::CameraMode::Driven),
+        RenderCommand::SetCamera {
+            position,
+            yaw,
+            pitch,
+        },
+    ]
+}
+
+#[test]
+fn test_chunk_matches_golden() {
+    let Some(image) = render_scene(camera([5.0, 6.0, 16.0], -90.0, -25.0)) else {
+        return;
+    };
+    assert_matches_golden("test_chunk", &image);
+}
+
+#[test]
+fn single_cube_matches_golden() {
+    let mut chunk = ChunkStorage::empty();
+    chunk.set(0, 0, 0, 1);
+    let mut commands = vec![RenderCommand::LoadChunk(ChunkPos::default(), chunk)];
+    commands.extend(camera([2.5, 2.5, 3.5], -123.7, -29.0));
+
+    let Some(image) = render_scene(commands) else {
+        return;
+    };
+    assert_matches_golden("single_cube", &image);
+}
+
+#[test]
+fn rocket_matches_golden() {
+    // Chunk de test retiré : la fusée seule, à l'origine
+    let rocket = EntityState {
+        id: 1,
+        model_id: 1,
+        transform: Instance::new(
+            Vector3::new(0.0, 0.0, 0.0),
+            Quaternion::new(1.0, 0.0, 0.0, 0.0),
+        ),
+    };
+    let mut commands = vec![
+        RenderCommand::UnloadChunk(ChunkPos::default()),
+        RenderCommand::SyncEntities(vec![rocket], instant::Instant::now()),
+    ];
+    commands.extend(camera([0.0, 1.0, 20.0], -90.0, 0.0));
+
+    let Some(image) = render_scene(commands) else {
+        return;
+    };
+    assert_matches_golden("rocket", &image);
+}
+
+#[test]
+fn comparison_marks_pixels_beyond_tolerance() {
+    let expected = RgbaImage::from_pixel(2, 1, Rgba([100, 100, 100, 255]));
+    let mut actual = expected.clone();
+    actual.put_pixel(0, 0, Rgba([104, 100, 100, 255]));
+    actual.put_pixel(1, 0, Rgba([100, 130, 100, 255]));
+
+    let comparison = compare(&actual, &expected, 4);
+    assert_eq!(comparison.mismatched, 1);
+    assert_eq!(comparison.max_difference, 30);
+    assert_eq!(comparison.diff.get_pixel(1, 0).0, [255, 0, 0, 255]);
+    assert_ne!(comparison.diff.get_pixel(0, 0).0, [255, 0, 0, 255]);
+}
@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use crate::command::RenderCommand;
+use crate::render_backend::adapter::{request_device, AdapterConfig};
+use crate::render_backend::readback::read_texture;
+use crate::render_backend::renderer::Renderer;
+
+/// Durée maximale d'attente des threads de meshing avant une image
+const MESHING_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Renderer sans fenêtre : chaque image est dessinée dans une texture puis relue par le CPU
+pub struct HeadlessRenderer {
+    renderer: Renderer,
+    target: wgpu::Texture,
+}
+
+impl HeadlessRenderer {
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
+
+    /// GPU choisi comme pour la fenêtre, via `MINI_GAME_BACKEND` et `MINI_GAME_FORCE_FALLBACK_ADAPTER`
+    pub async fn new(width: u32, height: u32) -> anyhow::Result<Self> {
+        Self::with_adapter_config(&AdapterConfig::from_env()?, width, height).await
+    }
+
+    pub async fn with_adapter_config(
+        adapter_config: &AdapterConfig,
+        width: u32,
+        height: u32,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(width > 0 && height > 0, "Invalid image size {}x{}", width, height);
+
+        let instance = adapter_config.create_instance();
+        let adapter = adapter_config.request_adapter(&instance, None).await?;
+        let (device, queue) = request_device(&adapter).await?;
+
+        let target = create_target(&device, Self::FORMAT, width, height);
+        let renderer = Renderer::new(device, queue, Self::FORMAT, width, height)?;
+        Ok(Self { renderer, target })
+    }
+
+    pub fn apply_commands(
+        &mut self,
+        commands: impl IntoIterator<Item = RenderCommand>,
+    ) -> anyhow::Result<()> {
+        self.renderer.apply_commands(commands)
+    }
+
+    /// Dessiner la scène une fois tous les chunks maillés, et renvoyer l'image
+    pub fn render_to_image(&mut self) -> anyhow::Result<image::RgbaImage> {
+        self.renderer.finish_meshing(MESHING_TIMEOUT)?;
+        self.renderer.update(Duration::ZERO);
+
+        let view = self
+            .target
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        self.renderer.render_to(&view);
+        read_texture(self.renderer.device(), self.renderer.queue(), &self.target)
+    }
+}
+
+/// Texture de rendu lisible par `Readback`
+pub(crate) fn create_target(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Target"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
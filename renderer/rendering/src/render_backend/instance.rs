@@ -1,4 +1,5 @@
-use cgmath::{Matrix4, Quaternion, Vector3};
+use cgmath::{Matrix4, Quaternion, Vector3, VectorSpace};
+use bytemuck::Zeroable;
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 
 #[repr(C)]
@@ -38,6 +39,7 @@ impl InstanceRaw {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Instance {
     pub position: Vector3<f32>,
     pub rotation: Quaternion<f32>,
@@ -48,7 +50,15 @@ impl Instance {
         Self { position, rotation }
     }
 
-    fn to_raw(&self) -> InstanceRaw {
+    /// Pose intermédiaire : position interpolée linéairement, rotation par slerp
+    pub fn interpolate(self, target: Instance, amount: f32) -> Self {
+        Self {
+            position: self.position.lerp(target.position, amount),
+            rotation: self.rotation.slerp(target.rotation, amount),
+        }
+    }
+
+    fn to_raw(self) -> InstanceRaw {
         InstanceRaw {
             model: (Matrix4::from_translation(self.position)
                 * Matrix4::from(self.rotation))
@@ -60,12 +70,14 @@ impl Instance {
 pub struct InstanceBuffer {
     buffer: wgpu::Buffer,
     instances: Vec<Instance>,
+    /// Nombre d'instances que le buffer GPU peut contenir
+    capacity: usize,
 }
 
 impl InstanceBuffer {
     pub fn new(device: &wgpu::Device, instances: Vec<Instance>) -> Self {
         let instance_data: Vec<InstanceRaw> =
-            instances.iter().map(Instance::to_raw).collect();
+            instances.iter().map(|instance| instance.to_raw()).collect();
 
         let buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("Instance Buffer"),
@@ -73,19 +85,34 @@ impl InstanceBuffer {
             usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
         });
 
-        Self { buffer, instances }
+        let capacity = instances.len();
+        Self { buffer, instances, capacity }
     }
 
-    pub fn update(&mut self, queue: &wgpu::Queue) {
-        let instance_data: Vec<InstanceRaw> =
-            self.instances.iter().map(Instance::to_raw).collect();
-        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&instance_data));
+    /// Remplacer toutes les instances, en agrandissant le buffer GPU si besoin
+    pub fn replace(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, instances: Vec<Instance>) {
+        self.instances = instances;
+        if self.instances.len() <= self.capacity {
+            self.update(queue);
+            return;
+        }
+
+        // Doubler la capacité pour ne pas recréer le buffer à chaque nouvelle instance
+        self.capacity = self.instances.len().next_power_of_two();
+        let mut instance_data: Vec<InstanceRaw> =
+            self.instances.iter().map(|instance| instance.to_raw()).collect();
+        instance_data.resize(self.capacity, InstanceRaw::zeroed());
+        self.buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Instance Buffer"),
+            contents: bytemuck::cast_slice(&instance_data),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
     }
 
-    pub fn update_instance(&mut self, index: usize, position: Vector3<f32>) {
-        if index < self.instances.len() {
-            self.instances[index].position = position;
-        }
+    fn update(&self, queue: &wgpu::Queue) {
+        let instance_data: Vec<InstanceRaw> =
+            self.instances.iter().map(|instance| instance.to_raw()).collect();
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&instance_data));
     }
 
     pub fn buffer(&self) -> &wgpu::Buffer {
@@ -99,4 +126,4 @@ impl InstanceBuffer {
     pub fn vertex_buffer_layout() -> wgpu::VertexBufferLayout<'static> {
         InstanceRaw::desc()
     }
-}
\ No newline at end of file
+}
@@ -1,87 +1,107 @@
+use crate::render_backend::model::MaterialData;
 use crate::texture::Texture;
 use wgpu::util::DeviceExt;
 
+/// Traitement de l'alpha, comme `alphaMode` en glTF
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum AlphaMode {
+    /// Alpha ignoré
+    #[default]
+    Opaque,
+    /// Fragment jeté sous le seuil, opaque sinon
+    Mask(f32),
+    /// Mélangé avec ce qui est déjà dessiné, sans écrire la profondeur
+    Blend,
+}
+
+impl AlphaMode {
+    /// Valeurs de `alpha_mode` dans le shader
+    fn shader_mode(self) -> u32 {
+        match self {
+            AlphaMode::Opaque => 0,
+            AlphaMode::Mask(_) => 1,
+            AlphaMode::Blend => 2,
+        }
+    }
+
+    fn cutoff(self) -> f32 {
+        match self {
+            AlphaMode::Mask(cutoff) => cutoff,
+            _ => 0.0,
+        }
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
 struct MaterialUniform {
     color: [f32; 4],      // 16 bytes
     use_texture: u32,     // 4 bytes
-    _padding: [u32; 7],   // 28 bytes -> Total: 48 bytes
+    alpha_mode: u32,      // 4 bytes
+    alpha_cutoff: f32,    // 4 bytes
+    _padding: [u32; 5],   // 20 bytes -> Total: 48 bytes
 }
 
+impl MaterialUniform {
+    fn new(color: [f32; 4], use_texture: bool, alpha_mode: AlphaMode) -> Self {
+        Self {
+            color,
+            use_texture: use_texture as u32,
+            alpha_mode: alpha_mode.shader_mode(),
+            alpha_cutoff: alpha_mode.cutoff(),
+            _padding: [0; 5],
+        }
+    }
+}
+
+/// Cloner un matériau partage ses buffers GPU (même couleur pour tous les clones)
+#[derive(Clone)]
 pub struct Material {
-    pub diffuse_texture: Option<Texture>,
     pub bind_group: wgpu::BindGroup,
-    pub color: [f32; 4],
-    material_buffer: wgpu::Buffer,
+    alpha_mode: AlphaMode,
 }
 
 impl Material {
-    /// Créer un matériau avec texture
-    pub fn with_texture(
+    /// Créer un matériau avec couleur uniquement
+    pub fn with_color(
         device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        texture_bytes: &[u8],
+        color: [f32; 4],
         label: &str,
     ) -> anyhow::Result<Self> {
-        let diffuse_texture = Texture::from_bytes(device, queue, texture_bytes, label)?;
-
-        let material_uniform = MaterialUniform {
-            color: [1.0, 1.0, 1.0, 1.0],
-            use_texture: 1,
-            _padding: [0; 7],
-        };
-
-        let material_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some(&format!("{}_material_buffer", label)),
-            contents: bytemuck::cast_slice(&[material_uniform]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
-
-        let layout = Self::create_bind_group_layout(device);
-
-        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some(&format!("{}_bind_group", label)),
-            layout: &layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&diffuse_texture.view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 2,
-                    resource: material_buffer.as_entire_binding(),
-                },
-            ],
-        });
+        Self::create(device, None, color, AlphaMode::Opaque, label)
+    }
 
-        Ok(Self {
-            diffuse_texture: Some(diffuse_texture),
-            bind_group,
-            color: [1.0, 1.0, 1.0, 1.0],
-            material_buffer,
-        })
+    /// Créer un matériau glTF : la couleur de base multiplie la texture s'il y en a une
+    pub fn from_data(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        data: &MaterialData,
+    ) -> anyhow::Result<Self> {
+        let diffuse_texture = data
+            .base_color_texture
+            .as_ref()
+            .map(|texture| {
+                Texture::from_rgba(device, queue, &texture.image, &texture.sampler, Some(&data.name))
+            })
+            .transpose()?;
+        Self::create(device, diffuse_texture, data.base_color, data.alpha_mode, &data.name)
     }
 
-    /// Créer un matériau avec couleur uniquement
-    pub fn with_color(
+    fn create(
         device: &wgpu::Device,
+        diffuse_texture: Option<Texture>,
         color: [f32; 4],
+        alpha_mode: AlphaMode,
         label: &str,
     ) -> anyhow::Result<Self> {
-        // Créer une texture 1x1 transparente par défaut
-        let dummy_texture = Texture::create_dummy(device, label)?;
-
-        let material_uniform = MaterialUniform {
-            color,
-            use_texture: 0,
-            _padding: [0; 7],
+        // Sans texture, une texture 1x1 remplit quand même le bind group
+        let bound_texture = match &diffuse_texture {
+            Some(texture) => texture.clone(),
+            None => Texture::create_dummy(device, label)?,
         };
 
+        let material_uniform = MaterialUniform::new(color, diffuse_texture.is_some(), alpha_mode);
+
         let material_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some(&format!("{}_material_buffer", label)),
             contents: bytemuck::cast_slice(&[material_uniform]),
@@ -96,11 +116,11 @@ impl Material {
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&dummy_texture.view),
+                    resource: wgpu::BindingResource::TextureView(&bound_texture.view),
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&dummy_texture.sampler),
+                    resource: wgpu::BindingResource::Sampler(&bound_texture.sampler),
                 },
                 wgpu::BindGroupEntry {
                     binding: 2,
@@ -110,22 +130,14 @@ impl Material {
         });
 
         Ok(Self {
-            diffuse_texture: None,
             bind_group,
-            color,
-            material_buffer,
+            alpha_mode,
         })
     }
 
-    /// Mettre à jour la couleur
-    pub fn update_color(&mut self, queue: &wgpu::Queue, color: [f32; 4]) {
-        self.color = color;
-        let material_uniform = MaterialUniform {
-            color,
-            use_texture: if self.diffuse_texture.is_some() { 1 } else { 0 },
-            _padding: [0; 7],
-        };
-        queue.write_buffer(&self.material_buffer, 0, bytemuck::cast_slice(&[material_uniform]));
+    /// Les matériaux `Blend` sont dessinés après les autres, avec le pipeline de transparence
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
     }
 
     pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
@@ -165,4 +177,4 @@ impl Material {
     pub fn bind_group(&self) -> &wgpu::BindGroup {
         &self.bind_group
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,16 @@
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
-use crate::render_backend::glb_loader::GlbFile;
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, Default, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vertex {
     pub position: [f32; 3],
     pub tex_coords: [f32; 2],
+    /// Normale unitaire, dans le même espace que `position`
+    pub normal: [f32; 3],
+    /// Articulations du skin (indices dans `Skeleton`), ignorées si tous les poids sont nuls
+    pub joints: [u8; 4],
+    /// Poids des articulations, de 0 à 255 (renormalisés dans le shader)
+    pub weights: [u8; 4],
 }
 
 impl Vertex {
@@ -24,47 +29,62 @@ impl Vertex {
                     shader_location: 1,
                     format: wgpu::VertexFormat::Float32x2,
                 },
+                wgpu::VertexAttribute {
+                    offset: size_of::<[f32; 5]>() as wgpu::BufferAddress,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float32x3,
+                },
+                wgpu::VertexAttribute {
+                    offset: size_of::<[f32; 8]>() as wgpu::BufferAddress,
+                    shader_location: 2,
+                    format: wgpu::VertexFormat::Uint8x4,
+                },
+                wgpu::VertexAttribute {
+                    offset: (size_of::<[f32; 8]>() + size_of::<[u8; 4]>()) as wgpu::BufferAddress,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Unorm8x4,
+                },
             ],
         }
     }
 }
 
+/// Indices prêts à envoyer au GPU : `u16` tant que les sommets le permettent, `u32` au-delà
+fn pack_indices(vertex_count: usize, indices: &[u32]) -> anyhow::Result<(wgpu::IndexFormat, Vec<u8>)> {
+    anyhow::ensure!(
+        vertex_count <= u32::MAX as usize,
+        "Mesh has {} vertices, more than a u32 index buffer can address",
+        vertex_count
+    );
+    if let Some(&index) = indices.iter().find(|&&i| i as usize >= vertex_count) {
+        anyhow::bail!("Index {} out of range for {} vertices", index, vertex_count);
+    }
+
+    if vertex_count <= u16::MAX as usize {
+        let indices: Vec<u16> = indices.iter().map(|&i| i as u16).collect();
+        Ok((wgpu::IndexFormat::Uint16, bytemuck::cast_slice(&indices).to_vec()))
+    } else {
+        Ok((wgpu::IndexFormat::Uint32, bytemuck::cast_slice(indices).to_vec()))
+    }
+}
+
 /// Représente un mesh avec ses buffers GPU
 pub struct Mesh {
     pub(crate) vertex_buffer: wgpu::Buffer,
     pub(crate) index_buffer: wgpu::Buffer,
+    pub(crate) index_format: wgpu::IndexFormat,
     pub(crate) num_indices: u32,
 }
 
 impl Mesh {
-    pub fn from_glb(device: &wgpu::Device, path: &str) -> anyhow::Result<Self> {
-        let model = GlbFile::load(path).unwrap();
-        let (vertices, indices) = model.extract_mesh_data().unwrap();
-
-        let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&*vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
-
-        let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(&*indices),
-            usage: wgpu::BufferUsages::INDEX,
-        });
-
-        Ok(Self {
-            vertex_buffer,
-            index_buffer,
-            num_indices: indices.len() as u32,
-        })
-    }
-
+    /// Créer les buffers GPU, en refusant les indices qui sortent de `vertices`
     pub fn from_vertices(
         device: &wgpu::Device,
         vertices: &[Vertex],
-        indices: &[u16],
-    ) -> Self {
+        indices: &[u32],
+    ) -> anyhow::Result<Self> {
+        let (index_format, index_data) = pack_indices(vertices.len(), indices)?;
+
         let vertex_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("Vertex Buffer"),
             contents: bytemuck::cast_slice(vertices),
@@ -73,15 +93,16 @@ impl Mesh {
 
         let index_buffer = device.create_buffer_init(&BufferInitDescriptor {
             label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(indices),
+            contents: &index_data,
             usage: wgpu::BufferUsages::INDEX,
         });
 
-        Self {
+        Ok(Self {
             vertex_buffer,
             index_buffer,
+            index_format,
             num_indices: indices.len() as u32,
-        }
+        })
     }
 
     pub fn vertex_buffer(&self) -> &wgpu::Buffer {
@@ -92,7 +113,41 @@ impl Mesh {
         &self.index_buffer
     }
 
+    pub fn index_format(&self) -> wgpu::IndexFormat {
+        self.index_format
+    }
+
     pub fn num_indices(&self) -> u32 {
         self.num_indices
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_meshes_use_u16_indices() {
+        let (format, data) = pack_indices(4, &[0, 1, 2, 2, 3, 0]).unwrap();
+        assert_eq!(format, wgpu::IndexFormat::Uint16);
+        assert_eq!(data, bytemuck::cast_slice::<u16, u8>(&[0, 1, 2, 2, 3, 0]));
+    }
+
+    #[test]
+    fn large_meshes_switch_to_u32_indices() {
+        let vertex_count = u16::MAX as usize + 1;
+        let (format, data) = pack_indices(vertex_count, &[0, 65535, 1]).unwrap();
+        assert_eq!(format, wgpu::IndexFormat::Uint32);
+        assert_eq!(data, bytemuck::cast_slice::<u32, u8>(&[0, 65535, 1]));
+
+        let (format, _) = pack_indices(u16::MAX as usize, &[65534]).unwrap();
+        assert_eq!(format, wgpu::IndexFormat::Uint16);
+    }
+
+    #[test]
+    fn out_of_range_indices_are_rejected() {
+        let err = pack_indices(3, &[0, 1, 3]).unwrap_err();
+        assert!(err.to_string().contains("Index 3 out of range"));
+        assert!(pack_indices(70_000, &[0, 70_000]).is_err());
+    }
 }
\ No newline at end of file
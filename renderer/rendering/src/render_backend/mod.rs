@@ -1,3 +1,4 @@
+mod adapter;
 mod context;
 mod pipeline;
 pub(crate) mod mesh;
@@ -5,11 +6,23 @@ pub(crate) mod instance;
 mod material;
 mod scene;
 mod state;
-mod glb_loader;
+mod renderer;
+mod readback;
+mod headless;
+pub(crate) mod screenshot;
+#[cfg(test)]
+pub(crate) mod golden_tests;
+mod model;
+mod animation;
+mod skin;
 
 pub use pipeline::RenderPipelineBuilder;
 pub use mesh::{Mesh, Vertex};
 pub use instance::InstanceBuffer;
-pub use material::Material;
-pub use scene::{Scene, SceneObject};
-pub use state::State;
\ No newline at end of file
+pub use material::{AlphaMode, Material};
+pub use scene::SceneObject;
+pub use model::{Model, ModelData};
+pub use skin::SkinBuffer;
+pub use animation::JointMatrix;
+pub use state::State;
+pub use headless::HeadlessRenderer;
\ No newline at end of file
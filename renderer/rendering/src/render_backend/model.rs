@@ -0,0 +1,710 @@
+use anyhow::Context;
+use cgmath::{InnerSpace, Matrix, Matrix3, Matrix4, Point3, SquareMatrix, Transform, Vector3};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::render_backend::animation::{AnimationClip, Skeleton};
+use crate::render_backend::{AlphaMode, Material, Mesh, Vertex};
+
+/// Partie d'un modèle : une primitive glTF, dessinée avec sa propre plage d'indices
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubMesh {
+    /// Nom du mesh glTF d'origine, pour les logs
+    pub name: String,
+    pub indices: Range<u32>,
+    /// Index dans `ModelData::materials`
+    pub material: usize,
+}
+
+/// Texture de couleur de base décodée, avec l'échantillonnage demandé par le fichier
+#[derive(Debug, Clone)]
+pub struct TextureData {
+    pub image: image::RgbaImage,
+    pub sampler: wgpu::SamplerDescriptor<'static>,
+}
+
+/// Matériau PBR glTF réduit à ce que le shader utilise
+#[derive(Debug, Clone)]
+pub struct MaterialData {
+    pub name: String,
+    pub base_color: [f32; 4],
+    pub base_color_texture: Option<TextureData>,
+    pub alpha_mode: AlphaMode,
+}
+
+impl Default for MaterialData {
+    /// Matériau par défaut de la spécification glTF : blanc, opaque
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            base_color_texture: None,
+            alpha_mode: AlphaMode::Opaque,
+        }
+    }
+}
+
+/// Géométrie d'un fichier glTF côté CPU : toutes les primitives fusionnées,
+/// avec les transformations des nœuds déjà appliquées aux sommets.
+///
+/// Les primitives d'un skin restent dans l'espace du modèle et suivent les animations ;
+/// les autres sont figées dans leur pose de repos.
+#[derive(Debug, Default)]
+pub struct ModelData {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
+    pub sub_meshes: Vec<SubMesh>,
+    /// Matériaux du fichier, suivis du matériau par défaut des primitives qui n'en ont pas
+    pub materials: Vec<MaterialData>,
+    pub skeleton: Skeleton,
+    pub animations: Vec<AnimationClip>,
+}
+
+impl ModelData {
+    pub fn from_glb_slice(bytes: &[u8]) -> anyhow::Result<Self> {
+        let (document, buffers, images) =
+            gltf::import_slice(bytes).context("Failed to parse glTF data")?;
+        Self::from_document(&document, &buffers, &images)
+    }
+
+    /// Charger un fichier `.gltf` ou `.glb` ; ses buffers et images externes sont lus
+    /// à côté du fichier
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        // Les images externes sont résolues par rapport au fichier
+        let (document, buffers, images) = gltf::import(path)
+            .with_context(|| format!("Failed to load glTF file {}", path.display()))?;
+        Self::from_document(&document, &buffers, &images)
+            .with_context(|| format!("Invalid glTF model {}", path.display()))
+    }
+
+    fn from_document(
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        images: &[gltf::image::Data],
+    ) -> anyhow::Result<Self> {
+        let mut materials = document
+            .materials()
+            .map(|material| read_material(&material, images))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        materials.push(MaterialData::default());
+
+        // La scène par défaut, sinon toutes les scènes du fichier
+        let scenes: Vec<gltf::Scene> = match document.default_scene() {
+            Some(scene) => vec![scene],
+            None => document.scenes().collect(),
+        };
+        anyhow::ensure!(!scenes.is_empty(), "glTF file has no scene");
+
+        let skeleton = Skeleton::from_document(document, buffers)?;
+        anyhow::ensure!(
+            skeleton.joint_count() <= u8::MAX as usize + 1,
+            "Model has {} joints, at most 256 are supported",
+            skeleton.joint_count()
+        );
+        let animations = document
+            .animations()
+            .map(|animation| AnimationClip::read(&animation, buffers))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let mut data = ModelData {
+            materials,
+            skeleton,
+            animations,
+            ..Default::default()
+        };
+        for scene in scenes {
+            for node in scene.nodes() {
+                data.add_node(&node, Matrix4::identity(), buffers)?;
+            }
+        }
+        anyhow::ensure!(!data.sub_meshes.is_empty(), "glTF file has no triangle primitive");
+        Ok(data)
+    }
+
+    /// Ajouter un nœud et ses enfants, `parent` étant la transformation monde du parent
+    fn add_node(
+        &mut self,
+        node: &gltf::Node,
+        parent: Matrix4<f32>,
+        buffers: &[gltf::buffer::Data],
+    ) -> anyhow::Result<()> {
+        let transform = parent * Matrix4::from(node.transform().matrix());
+
+        if let Some(mesh) = node.mesh() {
+            let name = mesh
+                .name()
+                .map_or_else(|| format!("mesh_{}", mesh.index()), str::to_string);
+            // glTF ignore la transformation du nœud d'un mesh avec skin
+            let skin = node.skin().map(|skin| {
+                let offset = self.skeleton.skin_offset(skin.index()).unwrap_or_default();
+                (offset, skin.joints().count())
+            });
+            let mesh_transform = if skin.is_some() { Matrix4::identity() } else { transform };
+            for primitive in mesh.primitives() {
+                self.add_primitive(&name, &primitive, mesh_transform, skin, buffers)
+                    .with_context(|| {
+                        format!("Invalid primitive {} of {}", primitive.index(), name)
+                    })?;
+            }
+        }
+
+        for child in node.children() {
+            self.add_node(&child, transform, buffers)?;
+        }
+        Ok(())
+    }
+
+    fn add_primitive(
+        &mut self,
+        name: &str,
+        primitive: &gltf::Primitive,
+        transform: Matrix4<f32>,
+        skin: Option<(usize, usize)>,
+        buffers: &[gltf::buffer::Data],
+    ) -> anyhow::Result<()> {
+        if primitive.mode() != gltf::mesh::Mode::Triangles {
+            log::warn!(
+                "Skipping {:?} primitive in {}, only triangles are supported",
+                primitive.mode(),
+                name
+            );
+            return Ok(());
+        }
+
+        let reader =
+            primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| &data.0[..]));
+        let positions: Vec<[f32; 3]> = reader
+            .read_positions()
+            .context("Primitive has no positions")?
+            .collect();
+
+        // Coordonnées de texture facultatives
+        let tex_coords: Vec<[f32; 2]> = match reader.read_tex_coords(0) {
+            Some(tex_coords) => tex_coords.into_f32().collect(),
+            None => vec![[0.0, 0.0]; positions.len()],
+        };
+        anyhow::ensure!(
+            tex_coords.len() == positions.len(),
+            "Primitive has {} positions but {} tex coords",
+            positions.len(),
+            tex_coords.len()
+        );
+
+        // Sans indices, les sommets sont pris trois par trois
+        let mut indices: Vec<u32> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+        anyhow::ensure!(
+            indices.len().is_multiple_of(3),
+            "Primitive has {} indices, not triangles",
+            indices.len()
+        );
+        if let Some(&index) = indices.iter().find(|&&i| i as usize >= positions.len()) {
+            anyhow::bail!("Index {} out of range for {} vertices", index, positions.len());
+        }
+
+        // Normales du fichier, sinon lissées à partir des triangles
+        let normals: Vec<[f32; 3]> = match reader.read_normals() {
+            Some(normals) => normals.collect(),
+            None => smooth_normals(&positions, &indices),
+        };
+        anyhow::ensure!(
+            normals.len() == positions.len(),
+            "Primitive has {} positions but {} normals",
+            positions.len(),
+            normals.len()
+        );
+
+        let skinning = match skin {
+            Some((offset, joint_count)) => read_skin(&reader, positions.len(), offset, joint_count)?,
+            None => vec![([0; 4], [0; 4]); positions.len()],
+        };
+
+        // Les normales suivent l'inverse transposée, qui garde l'orthogonalité aux faces
+        let normal_matrix = Matrix3::from_cols(
+            transform.x.truncate(),
+            transform.y.truncate(),
+            transform.z.truncate(),
+        );
+        let normal_matrix = normal_matrix
+            .invert()
+            .map_or(normal_matrix, |inverse| inverse.transpose());
+
+        let base = self.vertices.len() as u32;
+        for (i, &position) in positions.iter().enumerate() {
+            let normal = normal_matrix * Vector3::from(normals[i]);
+            self.vertices.push(Vertex {
+                position: transform.transform_point(Point3::from(position)).into(),
+                tex_coords: tex_coords[i],
+                normal: normalize_or_up(normal).into(),
+                joints: skinning[i].0,
+                weights: skinning[i].1,
+            });
+        }
+
+        // Une transformation miroir inverse le sens des triangles
+        if transform.determinant() < 0.0 {
+            for triangle in indices.chunks_exact_mut(3) {
+                triangle.swap(1, 2);
+            }
+        }
+
+        let start = self.indices.len() as u32;
+        self.indices.extend(indices.iter().map(|&i| base + i));
+        self.sub_meshes.push(SubMesh {
+            name: name.to_string(),
+            indices: start..self.indices.len() as u32,
+            // Le matériau par défaut est le dernier
+            material: primitive.material().index().unwrap_or(self.materials.len() - 1),
+        });
+        Ok(())
+    }
+}
+
+/// Articulations (décalées dans le squelette du modèle) et poids quantifiés de chaque sommet
+fn read_skin<'a, 's>(
+    reader: &gltf::mesh::Reader<'a, 's, impl Clone + Fn(gltf::Buffer<'a>) -> Option<&'s [u8]>>,
+    vertex_count: usize,
+    offset: usize,
+    joint_count: usize,
+) -> anyhow::Result<Vec<([u8; 4], [u8; 4])>> {
+    let (Some(joints), Some(weights)) = (reader.read_joints(0), reader.read_weights(0)) else {
+        // Un mesh avec skin sans JOINTS_0 suit entièrement la première articulation
+        return Ok(vec![([offset as u8, 0, 0, 0], [255, 0, 0, 0]); vertex_count]);
+    };
+
+    let joints = joints
+        .into_u16()
+        .map(|joints| {
+            let mut shifted = [0u8; 4];
+            for (out, &joint) in shifted.iter_mut().zip(&joints) {
+                anyhow::ensure!(
+                    (joint as usize) < joint_count,
+                    "Joint {} out of range for a skin of {} joints",
+                    joint,
+                    joint_count
+                );
+                *out = (offset + joint as usize) as u8;
+            }
+            Ok(shifted)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let weights: Vec<[u8; 4]> = weights
+        .into_f32()
+        .map(|weights| weights.map(|w| (w.clamp(0.0, 1.0) * 255.0).round() as u8))
+        .collect();
+    anyhow::ensure!(
+        joints.len() == vertex_count && weights.len() == vertex_count,
+        "Primitive has {} positions but {} joints and {} weights",
+        vertex_count,
+        joints.len(),
+        weights.len()
+    );
+    Ok(joints.into_iter().zip(weights).collect())
+}
+
+/// Normales par sommet : moyenne des normales des triangles voisins, pondérée par leur aire
+fn smooth_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut sums = vec![Vector3::new(0.0, 0.0, 0.0); positions.len()];
+    for triangle in indices.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|k| Vector3::from(positions[triangle[k] as usize]));
+        let face = (b - a).cross(c - a);
+        for &index in triangle {
+            sums[index as usize] += face;
+        }
+    }
+    sums.into_iter().map(|sum| normalize_or_up(sum).into()).collect()
+}
+
+/// Normale unitaire, ou +Y pour un sommet sans triangle (ou dégénéré)
+fn normalize_or_up(normal: Vector3<f32>) -> Vector3<f32> {
+    if normal.magnitude2() > f32::EPSILON {
+        normal.normalize()
+    } else {
+        Vector3::unit_y()
+    }
+}
+
+fn read_material(
+    material: &gltf::Material,
+    images: &[gltf::image::Data],
+) -> anyhow::Result<MaterialData> {
+    let name = material
+        .name()
+        .map(str::to_string)
+        .or_else(|| material.index().map(|i| format!("material_{}", i)))
+        .unwrap_or_else(|| "default".to_string());
+    let pbr = material.pbr_metallic_roughness();
+
+    let alpha_mode = match material.alpha_mode() {
+        gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+        gltf::material::AlphaMode::Mask => AlphaMode::Mask(material.alpha_cutoff().unwrap_or(0.5)),
+        gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+    };
+
+    let base_color_texture = match pbr.base_color_texture() {
+        Some(info) => {
+            if info.tex_coord() != 0 {
+                log::warn!("Material {} uses TEXCOORD_{}, only TEXCOORD_0 is read", name, info.tex_coord());
+            }
+            let texture = info.texture();
+            let image = images
+                .get(texture.source().index())
+                .with_context(|| format!("Material {} references a missing image", name))?;
+            Some(TextureData {
+                image: to_rgba(image).with_context(|| format!("Invalid texture in material {}", name))?,
+                sampler: sampler_descriptor(&texture.sampler()),
+            })
+        }
+        None => None,
+    };
+
+    Ok(MaterialData {
+        name,
+        base_color: pbr.base_color_factor(),
+        base_color_texture,
+        alpha_mode,
+    })
+}
+
+/// Convertir une image glTF décodée (8, 16 bits ou flottants par canal) en RGBA 8 bits
+fn to_rgba(image: &gltf::image::Data) -> anyhow::Result<image::RgbaImage> {
+    use gltf::image::Format;
+    use image::{DynamicImage, ImageBuffer};
+
+    let (width, height) = (image.width, image.height);
+    // Les formats 16 et 32 bits sont stockés dans l'ordre des octets de la machine
+    let words = || -> Vec<u16> {
+        image.pixels.chunks_exact(2).map(|b| u16::from_ne_bytes([b[0], b[1]])).collect()
+    };
+    let floats = || -> Vec<f32> {
+        image
+            .pixels
+            .chunks_exact(4)
+            .map(|b| f32::from_ne_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    };
+    let pixels = image.pixels.clone();
+
+    let decoded = match image.format {
+        Format::R8 => ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8),
+        Format::R8G8 => ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageLumaA8),
+        Format::R8G8B8 => ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8),
+        Format::R8G8B8A8 => ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageRgba8),
+        Format::R16 => ImageBuffer::from_raw(width, height, words()).map(DynamicImage::ImageLuma16),
+        Format::R16G16 => ImageBuffer::from_raw(width, height, words()).map(DynamicImage::ImageLumaA16),
+        Format::R16G16B16 => ImageBuffer::from_raw(width, height, words()).map(DynamicImage::ImageRgb16),
+        Format::R16G16B16A16 => {
+            ImageBuffer::from_raw(width, height, words()).map(DynamicImage::ImageRgba16)
+        }
+        Format::R32G32B32FLOAT => {
+            ImageBuffer::from_raw(width, height, floats()).map(DynamicImage::ImageRgb32F)
+        }
+        Format::R32G32B32A32FLOAT => {
+            ImageBuffer::from_raw(width, height, floats()).map(DynamicImage::ImageRgba32F)
+        }
+    };
+    decoded
+        .map(|image| image.to_rgba8())
+        .context("Image data does not match its size")
+}
+
+/// Échantillonnage glTF (répétition et filtrage linéaire par défaut)
+fn sampler_descriptor(sampler: &gltf::texture::Sampler) -> wgpu::SamplerDescriptor<'static> {
+    use gltf::texture::{MagFilter, MinFilter, WrappingMode};
+
+    let address_mode = |mode: WrappingMode| match mode {
+        WrappingMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        WrappingMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+        WrappingMode::Repeat => wgpu::AddressMode::Repeat,
+    };
+    let mag_filter = match sampler.mag_filter() {
+        Some(MagFilter::Nearest) => wgpu::FilterMode::Nearest,
+        Some(MagFilter::Linear) | None => wgpu::FilterMode::Linear,
+    };
+    // Pas de mipmaps : seul le filtrage du niveau 0 compte
+    let min_filter = match sampler.min_filter() {
+        Some(MinFilter::Nearest | MinFilter::NearestMipmapNearest | MinFilter::NearestMipmapLinear) => {
+            wgpu::FilterMode::Nearest
+        }
+        _ => wgpu::FilterMode::Linear,
+    };
+
+    wgpu::SamplerDescriptor {
+        label: Some("gltf sampler"),
+        address_mode_u: address_mode(sampler.wrap_s()),
+        address_mode_v: address_mode(sampler.wrap_t()),
+        mag_filter,
+        min_filter,
+        ..Default::default()
+    }
+}
+
+/// Modèle glTF envoyé au GPU : un seul mesh partagé par toutes ses sous-parties
+pub struct Model {
+    pub mesh: Mesh,
+    pub sub_meshes: Vec<SubMesh>,
+    /// Matériaux GPU, dans l'ordre de `ModelData::materials`
+    pub materials: Vec<Material>,
+    pub skeleton: Skeleton,
+    pub animations: Vec<AnimationClip>,
+}
+
+impl Model {
+    pub fn new(device: &wgpu::Device, queue: &wgpu::Queue, data: &ModelData) -> anyhow::Result<Self> {
+        Ok(Self {
+            mesh: Mesh::from_vertices(device, &data.vertices, &data.indices)?,
+            sub_meshes: data.sub_meshes.clone(),
+            materials: data
+                .materials
+                .iter()
+                .map(|material| Material::from_data(device, queue, material))
+                .collect::<anyhow::Result<_>>()?,
+            skeleton: data.skeleton.clone(),
+            animations: data.animations.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_models_load_every_primitive() {
+        for bytes in [
+            &include_bytes!("../model/player.glb")[..],
+            &include_bytes!("../model/rocket.glb")[..],
+        ] {
+            let data = ModelData::from_glb_slice(bytes).unwrap();
+            assert!(!data.sub_meshes.is_empty());
+            assert_eq!(data.sub_meshes.last().unwrap().indices.end as usize, data.indices.len());
+            assert!(data.indices.iter().all(|&i| (i as usize) < data.vertices.len()));
+        }
+    }
+
+    #[test]
+    fn load_reports_missing_files() {
+        let err = ModelData::load("src/model/missing.glb").unwrap_err();
+        assert!(format!("{:#}", err).contains("missing.glb"));
+        assert!(ModelData::from_glb_slice(b"not a glb").is_err());
+    }
+
+    #[test]
+    fn load_resolves_external_images_next_to_the_file() {
+        // Triangle dont la texture est un PNG rouge 2×1 placé à côté du .gltf
+        let directory = std::env::temp_dir()
+            .join(format!("mini_game_external_texture_{}", std::process::id()));
+        std::fs::create_dir_all(directory.join("textures")).unwrap();
+        image::RgbaImage::from_pixel(2, 1, image::Rgba([255, 0, 0, 255]))
+            .save(directory.join("textures/red.png"))
+            .unwrap();
+        let gltf = r#"{
+            "asset": {"version": "2.0"},
+            "scenes": [{"nodes": [0]}],
+            "nodes": [{"mesh": 0}],
+            "meshes": [{"primitives": [{"attributes": {"POSITION": 0}, "material": 0}]}],
+            "materials": [{"pbrMetallicRoughness": {"baseColorTexture": {"index": 0}}}],
+            "textures": [{"source": 0}],
+            "images": [{"uri": "textures/red.png"}],
+            "accessors": [{
+                "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+            }],
+            "bufferViews": [{"buffer": 0, "byteLength": 36}],
+            "buffers": [{"byteLength": 36, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"}]
+        }"#;
+        let path = directory.join("triangle.gltf");
+        std::fs::write(&path, gltf).unwrap();
+
+        let data = ModelData::load(&path);
+        std::fs::remove_dir_all(&directory).unwrap();
+        let data = data.unwrap();
+        let texture = data.materials[data.sub_meshes[0].material]
+            .base_color_texture
+            .as_ref()
+            .unwrap();
+        assert_eq!(texture.image.dimensions(), (2, 1));
+        assert_eq!(texture.image.get_pixel(1, 0).0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn node_transforms_are_baked() {
+        // Un triangle sous un nœud parent translaté de (10, 0, 0) et un enfant mis à l'échelle x2
+        let gltf = r#"{
+            "asset": {"version": "2.0"},
+            "scene": 0,
+            "scenes": [{"nodes": [0]}],
+            "nodes": [
+                {"translation": [10.0, 0.0, 0.0], "children": [1]},
+                {"scale": [2.0, 2.0, 2.0], "mesh": 0}
+            ],
+            "meshes": [{"primitives": [{"attributes": {"POSITION": 0}}]}],
+            "accessors": [{
+                "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+            }],
+            "bufferViews": [{"buffer": 0, "byteLength": 36}],
+            "buffers": [{"byteLength": 36, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"}]
+        }"#;
+
+        let data = ModelData::from_glb_slice(gltf.as_bytes()).unwrap();
+        assert_eq!(data.indices, vec![0, 1, 2]);
+        let positions: Vec<[f32; 3]> = data.vertices.iter().map(|v| v.position).collect();
+        assert_eq!(positions, vec![[10.0, 0.0, 0.0], [12.0, 0.0, 0.0], [10.0, 2.0, 0.0]]);
+        // Pas de NORMAL dans le fichier : normale calculée depuis le triangle, restée unitaire
+        assert!(data.vertices.iter().all(|v| v.normal == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn builtin_normals_are_unit_length() {
+        let rocket = ModelData::from_glb_slice(include_bytes!("../model/rocket.glb")).unwrap();
+        for vertex in &rocket.vertices {
+            let length = Vector3::from(vertex.normal).magnitude();
+            assert!((length - 1.0).abs() < 1e-4, "{:?}", vertex.normal);
+        }
+    }
+
+    #[test]
+    fn smooth_normals_average_adjacent_faces() {
+        // Deux triangles en équerre partageant l'arête (0, 1)
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, -1.0]];
+        let normals = smooth_normals(&positions, &[0, 1, 2, 0, 1, 3]);
+        assert_eq!(normals[2], [0.0, 0.0, 1.0]);
+        assert_eq!(normals[3], [0.0, 1.0, 0.0]);
+        let shared = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((normals[0][1] - shared).abs() < 1e-6 && (normals[0][2] - shared).abs() < 1e-6);
+    }
+
+    #[test]
+    fn builtin_materials_are_read() {
+        let player = ModelData::from_glb_slice(include_bytes!("../model/player.glb")).unwrap();
+        let skin = &player.materials[player.sub_meshes[0].material];
+        assert_eq!(skin.name, "skin");
+        let texture = skin.base_color_texture.as_ref().unwrap();
+        assert!(texture.image.width() > 0 && texture.image.height() > 0);
+        assert_eq!(texture.sampler.mag_filter, wgpu::FilterMode::Nearest);
+        assert_eq!(texture.sampler.address_mode_u, wgpu::AddressMode::Repeat);
+
+        let rocket = ModelData::from_glb_slice(include_bytes!("../model/rocket.glb")).unwrap();
+        let material = &rocket.materials[rocket.sub_meshes[0].material];
+        assert!(material.base_color_texture.is_none());
+        assert_eq!(material.base_color, [0.8, 0.8, 0.8, 1.0]);
+        assert_eq!(material.alpha_mode, AlphaMode::Opaque);
+    }
+
+    #[test]
+    fn alpha_modes_and_default_material() {
+        // Deux primitives sur le même triangle : une masquée, une sans matériau
+        let gltf = r#"{
+            "asset": {"version": "2.0"},
+            "scenes": [{"nodes": [0]}],
+            "nodes": [{"mesh": 0}],
+            "meshes": [{"primitives": [
+                {"attributes": {"POSITION": 0}, "material": 1},
+                {"attributes": {"POSITION": 0}}
+            ]}],
+            "materials": [
+                {"name": "glass", "alphaMode": "BLEND"},
+                {"name": "leaves", "alphaMode": "MASK", "alphaCutoff": 0.25,
+                 "pbrMetallicRoughness": {"baseColorFactor": [0.1, 0.6, 0.2, 0.9]}}
+            ],
+            "accessors": [{
+                "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]
+            }],
+            "bufferViews": [{"buffer": 0, "byteLength": 36}],
+            "buffers": [{"byteLength": 36, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAA"}]
+        }"#;
+
+        let data = ModelData::from_glb_slice(gltf.as_bytes()).unwrap();
+        assert_eq!(data.materials.len(), 3);
+        assert_eq!(data.materials[0].alpha_mode, AlphaMode::Blend);
+
+        let leaves = &data.materials[data.sub_meshes[0].material];
+        assert_eq!(leaves.name, "leaves");
+        assert_eq!(leaves.alpha_mode, AlphaMode::Mask(0.25));
+        assert_eq!(leaves.base_color, [0.1, 0.6, 0.2, 0.9]);
+
+        let fallback = &data.materials[data.sub_meshes[1].material];
+        assert_eq!(fallback.base_color, [1.0, 1.0, 1.0, 1.0]);
+        assert_eq!(fallback.alpha_mode, AlphaMode::Opaque);
+    }
+
+    #[test]
+    fn player_rest_pose_keeps_the_bind_pose() {
+        let player = ModelData::from_glb_slice(include_bytes!("../model/player.glb")).unwrap();
+        assert!(player.skeleton.joint_count() > 0);
+        assert!(player.vertices.iter().any(|v| v.weights != [0; 4]));
+
+        let mut joints = Vec::new();
+        player.skeleton.joint_matrices(None, &mut joints);
+        let identity: [[f32; 4]; 4] = Matrix4::<f32>::identity().into();
+        for matrix in joints {
+            for (column, expected) in matrix.iter().zip(&identity) {
+                for (value, expected) in column.iter().zip(expected) {
+                    assert!((value - expected).abs() < 1e-3, "{:?}", matrix);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn skinned_triangle_follows_its_animation() {
+        // Triangle attaché à l'articulation 1 (nœud 2, enfant du nœud 1 décalé de 1 en x) ;
+        // l'animation "lift" monte le nœud 1 de 0 à 2 en une seconde
+        let gltf = r#"{
+            "asset": {"version": "2.0"},
+            "scenes": [{"nodes": [0, 1]}],
+            "nodes": [
+                {"mesh": 0, "skin": 0, "translation": [50.0, 0.0, 0.0]},
+                {"children": [2]},
+                {"translation": [1.0, 0.0, 0.0]}
+            ],
+            "skins": [{"joints": [1, 2]}],
+            "meshes": [{"primitives": [{"attributes": {"POSITION": 0, "JOINTS_0": 1, "WEIGHTS_0": 2}}]}],
+            "animations": [{
+                "name": "lift",
+                "channels": [{"sampler": 0, "target": {"node": 1, "path": "translation"}}],
+                "samplers": [{"input": 3, "output": 4}]
+            }],
+            "accessors": [
+                {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                 "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0]},
+                {"bufferView": 1, "componentType": 5121, "count": 3, "type": "VEC4"},
+                {"bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC4"},
+                {"bufferView": 3, "componentType": 5126, "count": 2, "type": "SCALAR",
+                 "min": [0.0], "max": [1.0]},
+                {"bufferView": 4, "componentType": 5126, "count": 2, "type": "VEC3"}
+            ],
+            "bufferViews": [
+                {"buffer": 0, "byteLength": 36},
+                {"buffer": 0, "byteOffset": 36, "byteLength": 12},
+                {"buffer": 0, "byteOffset": 48, "byteLength": 48},
+                {"buffer": 0, "byteOffset": 96, "byteLength": 8},
+                {"buffer": 0, "byteOffset": 104, "byteLength": 24}
+            ],
+            "buffers": [{"byteLength": 128, "uri": "data:application/octet-stream;base64,AAAAAAAAAAAAAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAQAAAAEAAAABAAAAAACAPwAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAIA/AAAAAAAAAAAAAAAAAAAAAAAAgD8AAAAAAAAAAAAAAAAAAAAAAAAAQAAAAAA="}]
+        }"#;
+
+        let data = ModelData::from_glb_slice(gltf.as_bytes()).unwrap();
+        assert_eq!(data.skeleton.joint_count(), 2);
+        // La translation du nœud du mesh est ignorée, le skin place les sommets
+        assert_eq!(data.vertices[1].position, [1.0, 0.0, 0.0]);
+        assert!(data
+            .vertices
+            .iter()
+            .all(|v| v.joints == [1, 0, 0, 0] && v.weights == [255, 0, 0, 0]));
+
+        let lift = &data.animations[0];
+        assert_eq!((lift.name.as_str(), lift.duration), ("lift", 1.0));
+
+        let mut joints = Vec::new();
+        data.skeleton.joint_matrices(None, &mut joints);
+        data.skeleton.joint_matrices(Some((lift, 0.5)), &mut joints);
+        assert_eq!(joints[1][3], [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(joints[3][3], [1.0, 1.0, 0.0, 1.0]);
+    }
+}
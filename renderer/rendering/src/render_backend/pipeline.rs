@@ -1,4 +1,4 @@
-use crate::render_backend::{InstanceBuffer, Material, Vertex};
+use crate::render_backend::{InstanceBuffer, Material, SkinBuffer, Vertex};
 use crate::texture::Texture;
 
 pub struct RenderPipelineBuilder {
@@ -14,24 +14,55 @@ impl RenderPipelineBuilder {
         &self,
         format: wgpu::TextureFormat,
         camera_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> wgpu::RenderPipeline {
+        self.build_with(format, camera_bind_group_layout, "Render Pipeline", wgpu::BlendState::REPLACE, true)
+    }
+
+    /// Pipeline des matériaux `AlphaMode::Blend` : mélange alpha, profondeur testée mais pas écrite
+    pub fn build_transparent(
+        &self,
+        format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> wgpu::RenderPipeline {
+        self.build_with(
+            format,
+            camera_bind_group_layout,
+            "Transparent Render Pipeline",
+            wgpu::BlendState::ALPHA_BLENDING,
+            false,
+        )
+    }
+
+    fn build_with(
+        &self,
+        format: wgpu::TextureFormat,
+        camera_bind_group_layout: &wgpu::BindGroupLayout,
+        label: &str,
+        blend: wgpu::BlendState,
+        depth_write_enabled: bool,
     ) -> wgpu::RenderPipeline {
         let shader = self
             .device
             .create_shader_module(wgpu::include_wgsl!("../shaders/shader.wgsl"));
 
         let texture_bind_group_layout = Material::create_bind_group_layout(&self.device);
+        let skin_bind_group_layout = SkinBuffer::create_bind_group_layout(&self.device);
 
         let pipeline_layout =
             self.device
                 .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                     label: Some("Render Pipeline Layout"),
-                    bind_group_layouts: &[&texture_bind_group_layout, camera_bind_group_layout],
+                    bind_group_layouts: &[
+                        &texture_bind_group_layout,
+                        camera_bind_group_layout,
+                        &skin_bind_group_layout,
+                    ],
                     push_constant_ranges: &[],
                 });
 
         self.device
             .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-                label: Some("Render Pipeline"),
+                label: Some(label),
                 layout: Some(&pipeline_layout),
                 vertex: wgpu::VertexState {
                     module: &shader,
@@ -50,7 +81,7 @@ impl RenderPipelineBuilder {
                 },
                 depth_stencil: Some(wgpu::DepthStencilState {
                     format: Texture::DEPTH_FORMAT,
-                    depth_write_enabled: true,
+                    depth_write_enabled,
                     depth_compare: wgpu::CompareFunction::Less,
                     stencil: wgpu::StencilState::default(),
                     bias: wgpu::DepthBiasState::default(),
@@ -66,7 +97,7 @@ impl RenderPipelineBuilder {
                     compilation_options: Default::default(),
                     targets: &[Some(wgpu::ColorTargetState {
                         format,
-                        blend: Some(wgpu::BlendState::REPLACE),
+                        blend: Some(blend),
                         write_mask: wgpu::ColorWrites::ALL,
                     })],
                 }),
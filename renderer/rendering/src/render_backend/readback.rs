@@ -0,0 +1,164 @@
+use anyhow::Context;
+use std::sync::mpsc::Receiver;
+
+/// Copie d'une texture RGBA ou BGRA 8 bits dans un buffer lisible par le CPU
+pub struct Readback {
+    buffer: wgpu::Buffer,
+    width: u32,
+    height: u32,
+    bytes_per_row: u32,
+    bgra: bool,
+}
+
+impl Readback {
+    /// Enregistrer la copie de `texture` dans `encoder`
+    pub fn copy(
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+    ) -> anyhow::Result<Self> {
+        let bgra = match texture.format() {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => false,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => true,
+            other => anyhow::bail!("Cannot read back texture format {:?}", other),
+        };
+        anyhow::ensure!(
+            texture.usage().contains(wgpu::TextureUsages::COPY_SRC),
+            "Texture was not created with COPY_SRC"
+        );
+
+        let (width, height) = (texture.width(), texture.height());
+        let bytes_per_row = padded_bytes_per_row(width);
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: bytes_per_row as u64 * height as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+
+        Ok(Self {
+            buffer,
+            width,
+            height,
+            bytes_per_row,
+            bgra,
+        })
+    }
+
+    /// Demander le mapping, une fois la copie soumise. Le résultat arrive sur le canal
+    /// pendant un `device.poll`.
+    pub fn map(&self) -> Receiver<Result<(), wgpu::BufferAsyncError>> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.buffer
+            .slice(..)
+            .map_async(wgpu::MapMode::Read, move |result| {
+                let _ = sender.send(result);
+            });
+        receiver
+    }
+
+    /// Lire le buffer mappé
+    pub fn to_image(&self) -> anyhow::Result<image::RgbaImage> {
+        let image = unpad_rows(
+            &self.buffer.slice(..).get_mapped_range(),
+            self.width,
+            self.height,
+            self.bytes_per_row,
+            self.bgra,
+        );
+        self.buffer.unmap();
+        image
+    }
+}
+
+/// Copier une texture vers le CPU, en attendant la fin du GPU
+pub fn read_texture(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    texture: &wgpu::Texture,
+) -> anyhow::Result<image::RgbaImage> {
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("Readback Encoder"),
+    });
+    let readback = Readback::copy(device, &mut encoder, texture)?;
+    let submission = queue.submit(std::iter::once(encoder.finish()));
+
+    let mapped = readback.map();
+    device.poll(wgpu::PollType::Wait {
+        submission_index: Some(submission),
+        timeout: None,
+    })?;
+    mapped
+        .recv()
+        .context("Readback buffer was dropped before being mapped")??;
+    readback.to_image()
+}
+
+/// Les lignes copiées depuis une texture doivent faire un multiple de 256 octets
+fn padded_bytes_per_row(width: u32) -> u32 {
+    (width * 4).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+}
+
+/// Retirer le remplissage de fin de ligne, et remettre les canaux dans l'ordre RGBA
+fn unpad_rows(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    bytes_per_row: u32,
+    bgra: bool,
+) -> anyhow::Result<image::RgbaImage> {
+    let row_len = width as usize * 4;
+    let mut pixels = Vec::with_capacity(row_len * height as usize);
+    for row in data.chunks(bytes_per_row as usize).take(height as usize) {
+        pixels.extend_from_slice(&row[..row_len]);
+    }
+    if bgra {
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+        }
+    }
+    image::RgbaImage::from_raw(width, height, pixels)
+        .context("Readback buffer is smaller than the texture")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_are_padded_to_the_copy_alignment() {
+        assert_eq!(padded_bytes_per_row(1), 256);
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+    }
+
+    #[test]
+    fn padding_is_stripped_and_bgra_swapped() {
+        // 2 × 2 pixels, lignes de 12 octets dont 4 de remplissage
+        let data = [
+            1, 2, 3, 4, 5, 6, 7, 8, 0, 0, 0, 0, //
+            9, 10, 11, 12, 13, 14, 15, 16, 0, 0, 0, 0,
+        ];
+        let rgba = unpad_rows(&data, 2, 2, 12, false).unwrap();
+        assert_eq!(rgba.get_pixel(1, 1).0, [13, 14, 15, 16]);
+
+        let bgra = unpad_rows(&data, 2, 2, 12, true).unwrap();
+        assert_eq!(bgra.get_pixel(0, 0).0, [3, 2, 1, 4]);
+        assert_eq!(bgra.get_pixel(1, 1).0, [15, 14, 13, 16]);
+
+        assert!(unpad_rows(&data[..12], 2, 2, 12, false).is_err());
+    }
+}
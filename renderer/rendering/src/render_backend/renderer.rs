@@ -0,0 +1,346 @@
+use std::time::Duration;
+use wgpu::util::DeviceExt;
+
+use crate::chunk_storage::ChunkStorage;
+use crate::camera::{Camera, CameraController, CameraMode, CameraUniform, Projection};
+use crate::render_backend::RenderPipelineBuilder;
+use crate::render_backend::{AlphaMode, SceneObject, SkinBuffer};
+use crate::command::RenderCommand;
+use crate::world::{ChunkPos, World};
+use crate::entity_renderer::EntityRenderer;
+use crate::light::{DirectionalLight, LightUniform};
+use crate::texture::Texture;
+
+/// Monde, entités et pipelines, indépendants de la cible : fenêtre ou texture hors écran
+pub struct Renderer {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    render_pipeline: wgpu::RenderPipeline,
+    transparent_pipeline: wgpu::RenderPipeline,
+    camera: Camera,
+    projection: Projection,
+    pub camera_controller: CameraController,
+    camera_mode: CameraMode,
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    camera_uniform: CameraUniform,
+    /// Bind group d'articulations des objets sans skin
+    rigid_skin: SkinBuffer,
+    depth_texture: Texture,
+    world: World,
+    chunk_renderer: crate::chunk_renderer::ChunkRenderer,
+    entity_renderer: EntityRenderer,
+}
+
+impl Renderer {
+    /// Renderer pour des cibles au format `format` de `width` × `height` pixels
+    pub fn new(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> anyhow::Result<Self> {
+        // Camera setup
+        let camera = Camera::new(
+            (0.0, 5.0, 10.0),
+            cgmath::Deg(-90.0),
+            cgmath::Deg(-20.0),
+        );
+        let projection = Projection::new(
+            width.max(1),
+            height.max(1),
+            cgmath::Deg(45.0),
+            0.1,
+            100.0,
+        );
+        let mut camera_uniform = CameraUniform::new();
+        camera_uniform.update_view_proj(&camera, &projection);
+
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Camera Buffer"),
+            contents: bytemuck::cast_slice(&[camera_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Soleil fixe, partagé avec la caméra dans le groupe 1
+        let light_uniform = LightUniform::new(&DirectionalLight::default());
+        let light_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Light Buffer"),
+            contents: bytemuck::cast_slice(&[light_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let camera_bind_group_layout = Self::create_camera_bind_group_layout(&device);
+
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Camera Bind Group"),
+            layout: &camera_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: camera_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: light_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        // Pipeline
+        let pipeline_builder = RenderPipelineBuilder::new(device.clone());
+        let render_pipeline = pipeline_builder.build(format, &camera_bind_group_layout);
+        let transparent_pipeline =
+            pipeline_builder.build_transparent(format, &camera_bind_group_layout);
+
+        let rigid_skin = SkinBuffer::rigid(&device);
+
+        // Depth texture
+        let depth_texture =
+            Texture::create_depth_texture(&device, width, height, Some("Depth Texture"));
+
+        // Initialiser le gestionnaire de types de blocs et le renderer
+        let registry = crate::block_types::current()?;
+        let chunk_renderer = crate::chunk_renderer::ChunkRenderer::new(registry);
+
+        let mut world = World::new();
+
+        // Pour l'instant, créer un chunk de test
+        // Ce chunk sera remplacé par les données venant de Java via JNI
+        let mut test_chunk = ChunkStorage::empty();
+
+        // Ajouter quelques blocs de test
+        for x in 0..10 {
+            for z in 0..10 {
+                // Sol en pierre (type 2)
+                test_chunk.set(x, 0, z, 2);
+                // Quelques blocs de terre (type 1)
+                if x % 2 == 0 && z % 2 == 0 {
+                    test_chunk.set(x, 1, z, 1);
+                }
+            }
+        }
+
+        // Générer le mesh du chunk d'origine
+        world.insert_chunk(ChunkPos::default(), test_chunk);
+
+        Ok(Self {
+            device,
+            queue,
+            render_pipeline,
+            transparent_pipeline,
+            camera,
+            projection,
+            camera_controller: CameraController::new(4.0, 0.4),
+            camera_mode: CameraMode::default(),
+            camera_buffer,
+            camera_bind_group,
+            camera_uniform,
+            rigid_skin,
+            depth_texture,
+            world,
+            chunk_renderer,
+            entity_renderer: EntityRenderer::new(),
+        })
+    }
+
+    fn create_camera_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Camera Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &wgpu::Queue {
+        &self.queue
+    }
+
+    /// Adapter la projection et la profondeur à une nouvelle taille de cible
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        self.projection.resize(width, height);
+        self.depth_texture =
+            Texture::create_depth_texture(&self.device, width, height, Some("Depth Texture"));
+    }
+
+    pub fn update(&mut self, dt: Duration) {
+        if self.camera_mode == CameraMode::FreeFly {
+            self.camera_controller.update_camera(&mut self.camera, dt);
+        }
+        self.camera_uniform.update_view_proj(&self.camera, &self.projection);
+        self.queue.write_buffer(
+            &self.camera_buffer,
+            0,
+            bytemuck::cast_slice(&[self.camera_uniform]),
+        );
+
+        // Ne pas mettre à jour le chunk ici - uniquement via apply_commands()
+    }
+
+    /// Appliquer dans l'ordre les commandes reçues depuis Java et envoyer au GPU les meshes prêts
+    pub fn apply_commands(
+        &mut self,
+        commands: impl IntoIterator<Item = RenderCommand>,
+    ) -> anyhow::Result<()> {
+        let mut world_changed = false;
+        for command in commands {
+            match command {
+                RenderCommand::SetCamera { position, yaw, pitch } => {
+                    self.camera
+                        .set_pose(position, cgmath::Deg(yaw), cgmath::Deg(pitch));
+                }
+                RenderCommand::SetCameraFov(fovy) => self.projection.set_fovy(cgmath::Deg(fovy)),
+                RenderCommand::SetCameraMode(mode) => {
+                    // La souris a pu bouger pendant le mode `Driven`
+                    if mode != self.camera_mode {
+                        self.camera_controller.clear_pending_motion();
+                    }
+                    self.camera_mode = mode;
+                }
+                RenderCommand::LoadChunk(pos, chunk_data) => {
+                    self.world.insert_chunk(pos, chunk_data);
+                    world_changed = true;
+                }
+                RenderCommand::UnloadChunk(pos) => {
+                    world_changed |= self.world.remove_chunk(pos);
+                }
+                RenderCommand::SetBlocks(edits) => self.world.set_blocks(&edits),
+                RenderCommand::SyncEntities(entities, tick) => {
+                    self.entity_renderer.sync(&entities, tick);
+                }
+                RenderCommand::SetEntityAnimation(id, animation) => {
+                    self.entity_renderer.set_animation(id, animation);
+                }
+                RenderCommand::SetBlockRegistry(registry) => {
+                    log::info!("Using a block registry of {} blocks", registry.len());
+                    self.chunk_renderer.set_registry(registry);
+                    self.world.remesh_all();
+                }
+                // Gérés par la boucle d'événements et par `State`, qui a la surface
+                RenderCommand::TakeScreenshot(_) => {}
+            }
+        }
+        if world_changed {
+            log::debug!("World now has {} chunks", self.world.len());
+        }
+
+        // Poses des entités interpolées entre les deux derniers ticks
+        self.entity_renderer.update(&self.device, &self.queue, instant::Instant::now());
+
+        // Le meshing tourne en tâche de fond, on n'envoie ici que les meshes terminés
+        self.world
+            .upload_ready_meshes(&self.device, &mut self.chunk_renderer)?;
+        Ok(())
+    }
+
+    /// Attendre les threads de meshing et envoyer tous les meshes, pour une image complète
+    pub fn finish_meshing(&mut self, timeout: Duration) -> anyhow::Result<()> {
+        self.world
+            .upload_all_meshes(&self.device, &mut self.chunk_renderer, timeout)?;
+        Ok(())
+    }
+
+    /// Dessiner la scène dans `view`, qui doit avoir la taille passée à `resize`
+    pub fn render_to(&self, view: &wgpu::TextureView) -> wgpu::SubmissionIndex {
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Render Encoder"),
+            });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Render Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    depth_slice: None,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color {
+                            r: 0.75,
+                            g: 0.5,
+                            b: 0.25,
+                            a: 1.0,
+                        }),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_texture.view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                occlusion_query_set: None,
+                timestamp_writes: None,
+            });
+
+            render_pass.set_bind_group(1, &self.camera_bind_group, &[]);
+
+            // Render every loaded chunk, then every entity model
+            let objects = || self.world.objects().chain(self.entity_renderer.objects());
+            let is_blended = |object: &&SceneObject| object.material().alpha_mode() == AlphaMode::Blend;
+
+            render_pass.set_pipeline(&self.render_pipeline);
+            for object in objects().filter(|object| !is_blended(object)) {
+                draw_object(&mut render_pass, object, &self.rigid_skin);
+            }
+
+            // Les objets transparents passent en dernier, sur la profondeur des objets opaques
+            render_pass.set_pipeline(&self.transparent_pipeline);
+            for object in objects().filter(is_blended) {
+                draw_object(&mut render_pass, object, &self.rigid_skin);
+            }
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()))
+    }
+}
+
+fn draw_object(render_pass: &mut wgpu::RenderPass, object: &SceneObject, rigid_skin: &SkinBuffer) {
+    render_pass.set_bind_group(0, object.material().bind_group(), &[]);
+    let skin = object.skin().unwrap_or(rigid_skin.bind_group());
+    render_pass.set_bind_group(2, skin, &[]);
+    render_pass.set_vertex_buffer(0, object.mesh().vertex_buffer().slice(..));
+    render_pass.set_vertex_buffer(1, object.instance_buffer().slice(..));
+    render_pass.set_index_buffer(
+        object.mesh().index_buffer().slice(..),
+        object.mesh().index_format(),
+    );
+
+    render_pass.draw_indexed(
+        object.index_range(),
+        0,
+        0..object.instance_count(),
+    );
+}
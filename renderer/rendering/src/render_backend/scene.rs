@@ -1,103 +1,79 @@
 use crate::render_backend::mesh::Mesh;
 use crate::render_backend::material::Material;
-use crate::render_backend::instance::{Instance, InstanceBuffer};
-use cgmath::{Quaternion, Vector3, Deg, InnerSpace, Rotation3};
+use crate::render_backend::instance::InstanceBuffer;
+use std::ops::Range;
 
 pub struct SceneObject {
     mesh: Mesh,
     material: Material,
-    instance_buffer: InstanceBuffer,
+    /// Buffer GPU des instances, partagé entre les objets d'un même modèle ou chunk
+    instance_buffer: wgpu::Buffer,
+    instance_count: u32,
+    index_range: Range<u32>,
+    /// Articulations des instances, `None` pour un objet rigide
+    skin: Option<wgpu::BindGroup>,
 }
 
 impl SceneObject {
     pub fn new(
         mesh: Mesh,
         material: Material,
-        instance_buffer: InstanceBuffer,
+        instances: &InstanceBuffer,
     ) -> Self {
+        let index_range = 0..mesh.num_indices();
         Self {
             mesh,
             material,
-            instance_buffer,
+            instance_buffer: instances.buffer().clone(),
+            instance_count: instances.len() as u32,
+            index_range,
+            skin: None,
         }
     }
 
-    pub fn mesh(&self) -> &Mesh {
-        &self.mesh
+    /// Ne dessiner qu'une partie des indices du mesh (mesh partagé entre plusieurs objets)
+    pub fn with_index_range(mut self, index_range: Range<u32>) -> Self {
+        self.index_range = index_range;
+        self
     }
 
-    pub fn material(&self) -> &Material {
-        &self.material
+    /// Déformer le mesh par les articulations de ce bind group (voir `SkinBuffer`)
+    pub fn with_skin(mut self, skin: wgpu::BindGroup) -> Self {
+        self.skin = Some(skin);
+        self
     }
 
-    /// Accès mutable au matériau pour changer la couleur
-    pub fn material_mut(&mut self) -> &mut Material {
-        &mut self.material
+    pub fn set_skin(&mut self, skin: wgpu::BindGroup) {
+        self.skin = Some(skin);
     }
 
-    pub fn instance_buffer(&self) -> &InstanceBuffer {
-        &self.instance_buffer
+    /// Dessiner les instances de `instances` (à rappeler si son buffer GPU a été recréé)
+    pub fn set_instances(&mut self, instances: &InstanceBuffer) {
+        self.instance_buffer = instances.buffer().clone();
+        self.instance_count = instances.len() as u32;
     }
 
-    pub fn instance_buffer_mut(&mut self) -> &mut InstanceBuffer {
-        &mut self.instance_buffer
+    pub fn skin(&self) -> Option<&wgpu::BindGroup> {
+        self.skin.as_ref()
     }
-}
-
-pub struct Scene {
-    objects: Vec<SceneObject>,
-}
 
-impl Scene {
-    pub fn new() -> Self {
-        Self {
-            objects: Vec::new(),
-        }
+    pub fn mesh(&self) -> &Mesh {
+        &self.mesh
     }
 
-    pub fn add_object(&mut self, object: SceneObject) {
-        self.objects.push(object);
+    pub fn index_range(&self) -> Range<u32> {
+        self.index_range.clone()
     }
 
-    pub fn objects(&self) -> &[SceneObject] {
-        &self.objects
+    pub fn material(&self) -> &Material {
+        &self.material
     }
 
-    pub fn objects_mut(&mut self) -> &mut [SceneObject] {
-        &mut self.objects
+    pub fn instance_buffer(&self) -> &wgpu::Buffer {
+        &self.instance_buffer
     }
 
-    pub fn create_grid_instances(rows: u32, cols: u32) -> Vec<Instance> {
-        let displacement = Vector3::new(
-            rows as f32 * 0.5,
-            0.0,
-            cols as f32 * 0.5,
-        );
-
-        (0..rows)
-            .flat_map(|z| {
-                (0..cols).map(move |x| {
-                    let position = Vector3 {
-                        x: x as f32 * 4.0,
-                        y: 0.0,
-                        z: z as f32 * 4.0,
-                    } - displacement;
-
-                    let rotation = if position.magnitude() < 0.001 {
-                        Quaternion::from_axis_angle(Vector3::unit_z(), Deg(0.0))
-                    } else {
-                        Quaternion::from_axis_angle(position.normalize(), Deg(1.0))
-                    };
-
-                    Instance::new(position, rotation)
-                })
-            })
-            .collect()
+    pub fn instance_count(&self) -> u32 {
+        self.instance_count
     }
 }
-
-impl Default for Scene {
-    fn default() -> Self {
-        Self::new()
-    }
-}
\ No newline at end of file
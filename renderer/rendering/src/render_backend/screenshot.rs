@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, TryRecvError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::render_backend::readback::Readback;
+
+/// Copie de la swapchain en cours de mapping, et les fichiers à écrire
+struct PendingScreenshot {
+    readback: Readback,
+    mapped: Receiver<Result<(), wgpu::BufferAsyncError>>,
+    paths: Vec<PathBuf>,
+}
+
+/// Captures d'écran demandées (F2 ou Java) : la frame est copiée après le rendu, mappée
+/// sans attendre le GPU, puis encodée en PNG sur un autre thread
+#[derive(Default)]
+pub struct Screenshots {
+    requested: Vec<PathBuf>,
+    pending: Vec<PendingScreenshot>,
+}
+
+impl Screenshots {
+    /// Capturer la prochaine frame dans `path`
+    pub fn request(&mut self, path: PathBuf) {
+        self.requested.push(path);
+    }
+
+    pub fn is_requested(&self) -> bool {
+        !self.requested.is_empty()
+    }
+
+    /// Copier `texture` pour toutes les captures demandées et lancer son mapping
+    pub fn capture(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        texture: &wgpu::Texture,
+    ) -> anyhow::Result<()> {
+        let paths = std::mem::take(&mut self.requested);
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Screenshot Encoder"),
+        });
+        let readback = Readback::copy(device, &mut encoder, texture)?;
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let mapped = readback.map();
+        self.pending.push(PendingScreenshot {
+            readback,
+            mapped,
+            paths,
+        });
+        Ok(())
+    }
+
+    /// Écrire les captures dont le mapping est terminé, sans bloquer
+    pub fn poll(&mut self, device: &wgpu::Device) {
+        if self.pending.is_empty() {
+            return;
+        }
+        if let Err(e) = device.poll(wgpu::PollType::Poll) {
+            log::error!("Failed to poll device for screenshots: {}", e);
+        }
+
+        self.pending.retain(|screenshot| match screenshot.mapped.try_recv() {
+            Err(TryRecvError::Empty) => true,
+            Err(TryRecvError::Disconnected) => {
+                log::error!("Screenshot buffer was dropped before being mapped");
+                false
+            }
+            Ok(Err(e)) => {
+                log::error!("Failed to map screenshot buffer: {}", e);
+                false
+            }
+            Ok(Ok(())) => {
+                match screenshot.readback.to_image() {
+                    Ok(image) => save_in_background(image, screenshot.paths.clone()),
+                    Err(e) => log::error!("Failed to read screenshot: {}", e),
+                }
+                false
+            }
+        });
+    }
+}
+
+/// L'encodage PNG est lent : il ne doit pas retarder les frames suivantes
+fn save_in_background(image: image::RgbaImage, paths: Vec<PathBuf>) {
+    let spawned = std::thread::Builder::new()
+        .name("screenshot".to_string())
+        .spawn(move || {
+            for path in paths {
+                match save_png(&image, &path) {
+                    Ok(()) => log::info!("Saved screenshot {}", path.display()),
+                    Err(e) => log::error!("{}", e),
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("Failed to spawn screenshot thread: {}", e);
+    }
+}
+
+fn save_png(image: &image::RgbaImage, path: &Path) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    image
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| anyhow::anyhow!("Failed to write screenshot {}: {}", path.display(), e))
+}
+
+/// Dossier des captures faites avec F2 : `MINI_GAME_SCREENSHOT_DIR`, ou `screenshots`
+pub fn default_directory() -> PathBuf {
+    std::env::var_os("MINI_GAME_SCREENSHOT_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("screenshots"))
+}
+
+/// Fichier `screenshot-AAAAMMJJ-HHMMSS-mmm.png` (heure UTC) dans `directory`
+pub fn timestamped_path(directory: &Path, time: SystemTime) -> PathBuf {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    let second_of_day = seconds % 86_400;
+    directory.join(format!(
+        "screenshot-{:04}{:02}{:02}-{:02}{:02}{:02}-{:03}.png",
+        year,
+        month,
+        day,
+        second_of_day / 3600,
+        second_of_day / 60 % 60,
+        second_of_day % 60,
+        since_epoch.subsec_millis()
+    ))
+}
+
+/// Date grégorienne du jour `days` après le 1er janvier 1970 (algorithme de H. Hinnant)
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn days_convert_to_gregorian_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn screenshot_names_carry_the_utc_time() {
+        // 2024-03-05 07:08:09.042 UTC
+        let time = UNIX_EPOCH + Duration::from_millis(1_709_622_489_042);
+        assert_eq!(
+            timestamped_path(Path::new("shots"), time),
+            Path::new("shots").join("screenshot-20240305-070809-042.png")
+        );
+    }
+}
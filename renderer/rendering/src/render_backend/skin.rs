@@ -0,0 +1,122 @@
+use crate::render_backend::animation::JointMatrix;
+use cgmath::{Matrix4, SquareMatrix};
+use wgpu::util::{BufferInitDescriptor, DeviceExt};
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+struct SkinUniform {
+    joint_count: u32,
+    _padding: [u32; 3],
+}
+
+/// Matrices d'articulations de toutes les instances d'un modèle : celles de l'instance i
+/// commencent à `i * joint_count`
+pub struct SkinBuffer {
+    joint_buffer: wgpu::Buffer,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    /// Nombre de matrices que `joint_buffer` peut contenir
+    capacity: usize,
+}
+
+impl SkinBuffer {
+    pub fn new(device: &wgpu::Device, joint_count: usize, matrices: &[JointMatrix]) -> Self {
+        let uniform_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Skin Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[SkinUniform {
+                joint_count: joint_count as u32,
+                _padding: [0; 3],
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let (joint_buffer, capacity) = Self::create_joint_buffer(device, matrices);
+        let bind_group = Self::create_bind_group(device, &uniform_buffer, &joint_buffer);
+        Self {
+            joint_buffer,
+            uniform_buffer,
+            bind_group,
+            capacity,
+        }
+    }
+
+    /// Skin des objets rigides : aucune articulation, les sommets restent en place
+    pub fn rigid(device: &wgpu::Device) -> Self {
+        Self::new(device, 0, &[Matrix4::<f32>::identity().into()])
+    }
+
+    /// Remplacer les matrices, en agrandissant le buffer GPU si besoin (le bind group change alors)
+    pub fn replace(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, matrices: &[JointMatrix]) {
+        if matrices.len() <= self.capacity {
+            queue.write_buffer(&self.joint_buffer, 0, bytemuck::cast_slice(matrices));
+            return;
+        }
+        (self.joint_buffer, self.capacity) = Self::create_joint_buffer(device, matrices);
+        self.bind_group = Self::create_bind_group(device, &self.uniform_buffer, &self.joint_buffer);
+    }
+
+    fn create_joint_buffer(device: &wgpu::Device, matrices: &[JointMatrix]) -> (wgpu::Buffer, usize) {
+        // Doubler la capacité comme pour les instances ; un buffer de stockage n'est jamais vide
+        let capacity = matrices.len().max(1).next_power_of_two();
+        let mut data = matrices.to_vec();
+        data.resize(capacity, Matrix4::<f32>::identity().into());
+        let buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Joint Buffer"),
+            contents: bytemuck::cast_slice(&data),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+        (buffer, capacity)
+    }
+
+    fn create_bind_group(
+        device: &wgpu::Device,
+        uniform_buffer: &wgpu::Buffer,
+        joint_buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Skin Bind Group"),
+            layout: &Self::create_bind_group_layout(device),
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: joint_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    pub fn create_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("skin_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        })
+    }
+
+    pub fn bind_group(&self) -> &wgpu::BindGroup {
+        &self.bind_group
+    }
+}
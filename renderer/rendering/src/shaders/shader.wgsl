@@ -1,6 +1,9 @@
 struct VertexInput {
     @location(0) position: vec3<f32>,
-    @location(1) tex_coords: vec2<f32>
+    @location(1) tex_coords: vec2<f32>,
+    @location(2) joints: vec4<u32>,
+    @location(3) weights: vec4<f32>,
+    @location(4) normal: vec3<f32>,
 };
 
 struct InstanceInput {
@@ -12,28 +15,77 @@ struct InstanceInput {
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
-    @location(0) tex_coords: vec2<f32>
+    @location(0) tex_coords: vec2<f32>,
+    @location(1) world_normal: vec3<f32>,
 };
 
 struct CameraUniform {
     view_proj: mat4x4<f32>,
 };
 
+// alpha_mode : 0 opaque, 1 masque (seuil alpha_cutoff), 2 mélange
 struct MaterialUniform {
     color: vec4<f32>,
     use_texture: u32,
-    _padding: vec3<u32>,
+    alpha_mode: u32,
+    alpha_cutoff: f32,
+    _padding0: u32,
+    _padding1: vec4<u32>,
+};
+
+// joint_count = 0 pour les objets rigides
+// Bourrage en u32 séparés : un vec3<u32> serait aligné sur 16 octets (32 au total)
+struct SkinUniform {
+    joint_count: u32,
+    _padding0: u32,
+    _padding1: u32,
+    _padding2: u32,
+};
+
+// to_light : direction normalisée vers le soleil
+struct LightUniform {
+    to_light: vec3<f32>,
+    color: vec3<f32>,
+    ambient: vec3<f32>,
 };
 
 @group(1) @binding(0)
 var<uniform> camera: CameraUniform;
+@group(1) @binding(1)
+var<uniform> light: LightUniform;
+
+@group(2) @binding(0) var<uniform> skin: SkinUniform;
+@group(2) @binding(1) var<storage, read> joint_matrices: array<mat4x4<f32>>;
 
 @group(0) @binding(0) var t_diffuse: texture_2d<f32>;
 @group(0) @binding(1) var s_diffuse: sampler;
 @group(0) @binding(2) var<uniform> material: MaterialUniform;
 
+// Moyenne des matrices d'articulations de l'instance, pondérée par les poids du sommet
+fn skin_matrix(model: VertexInput, instance_index: u32) -> mat4x4<f32> {
+    let total = model.weights.x + model.weights.y + model.weights.z + model.weights.w;
+    if (skin.joint_count == 0u || total <= 0.0) {
+        return mat4x4<f32>(
+            vec4<f32>(1.0, 0.0, 0.0, 0.0),
+            vec4<f32>(0.0, 1.0, 0.0, 0.0),
+            vec4<f32>(0.0, 0.0, 1.0, 0.0),
+            vec4<f32>(0.0, 0.0, 0.0, 1.0),
+        );
+    }
+    let base = instance_index * skin.joint_count;
+    let weights = model.weights / total;
+    return joint_matrices[base + model.joints.x] * weights.x
+        + joint_matrices[base + model.joints.y] * weights.y
+        + joint_matrices[base + model.joints.z] * weights.z
+        + joint_matrices[base + model.joints.w] * weights.w;
+}
+
 @vertex
-fn vs_main(model: VertexInput, instance: InstanceInput) -> VertexOutput {
+fn vs_main(
+    model: VertexInput,
+    instance: InstanceInput,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
     let model_matrix = mat4x4<f32>(
         instance.model_matrix_0,
         instance.model_matrix_1,
@@ -42,15 +94,32 @@ fn vs_main(model: VertexInput, instance: InstanceInput) -> VertexOutput {
     );
     var out: VertexOutput;
     out.tex_coords = model.tex_coords;
-    out.clip_position = camera.view_proj * model_matrix * vec4<f32>(model.position, 1.0);
+    let skin = skin_matrix(model, instance_index);
+    let position = skin * vec4<f32>(model.position, 1.0);
+    out.clip_position = camera.view_proj * model_matrix * position;
+    // Instances sans échelle : la partie rotation suffit pour les normales
+    out.world_normal = (model_matrix * skin * vec4<f32>(model.normal, 0.0)).xyz;
     return out;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = material.color;
     if (material.use_texture != 0u) {
-        return textureSample(t_diffuse, s_diffuse, in.tex_coords);
-    } else {
-        return material.color;
+        color = color * textureSample(t_diffuse, s_diffuse, in.tex_coords);
     }
+    if (material.alpha_mode == 0u) {
+        color.a = 1.0;
+    } else if (material.alpha_mode == 1u) {
+        if (color.a < material.alpha_cutoff) {
+            discard;
+        }
+        color.a = 1.0;
+    }
+
+    // Lambert : ambiante partout, plus le soleil sur les faces tournées vers lui
+    let normal = normalize(in.world_normal);
+    let diffuse = max(dot(normal, light.to_light), 0.0);
+    let lighting = light.ambient + light.color * diffuse;
+    return vec4<f32>(color.rgb * lighting, color.a);
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
-use image::GenericImageView;
 use anyhow::*;
 
+#[derive(Clone)]
 pub struct Texture {
     #[allow(unused)]
     pub texture: wgpu::Texture,
@@ -11,14 +11,9 @@ pub struct Texture {
 impl Texture {
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
-    pub fn from_bytes(device: &wgpu::Device, queue: &wgpu::Queue, bytes: &[u8], label: &str) -> Result<Self> {
-        let img = image::load_from_memory(bytes)?;
-        Self::from_image(device, queue, &img, Some(label))
-    }
-
-    pub fn from_image(device: &wgpu::Device, queue: &wgpu::Queue, img: &image::DynamicImage, label: Option<&str>) -> Result<Self> {
-        let rgba = img.to_rgba8();
-        let dimensions = img.dimensions();
+    /// Texture sRGB avec un échantillonnage choisi par l'appelant (ex. celui d'un glTF)
+    pub fn from_rgba(device: &wgpu::Device, queue: &wgpu::Queue, rgba: &image::RgbaImage, sampler: &wgpu::SamplerDescriptor, label: Option<&str>) -> Result<Self> {
+        let dimensions = rgba.dimensions();
 
         let texture_size = wgpu::Extent3d {
             width: dimensions.0,
@@ -44,7 +39,7 @@ impl Texture {
                 origin: wgpu::Origin3d::ZERO,
                 aspect: wgpu::TextureAspect::All,
             },
-            &rgba,
+            rgba,
             wgpu::TexelCopyBufferLayout {
                 offset: 0,
                 bytes_per_row: Some(4 * dimensions.0),
@@ -54,25 +49,15 @@ impl Texture {
         );
 
         let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("diffuse sampler"),
-            address_mode_u: wgpu::AddressMode::ClampToEdge,
-            address_mode_v: wgpu::AddressMode::ClampToEdge,
-            address_mode_w: wgpu::AddressMode::ClampToEdge,
-            mag_filter: wgpu::FilterMode::Linear,
-            min_filter: wgpu::FilterMode::Nearest,
-            mipmap_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
+        let sampler = device.create_sampler(sampler);
 
         Ok(Self { texture, view, sampler })
     }
 
-    pub fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, label: Option<&str>) -> Self {
+    pub fn create_depth_texture(device: &wgpu::Device, width: u32, height: u32, label: Option<&str>) -> Self {
         let texture_size = wgpu::Extent3d {
-            width: config.width.max(1),
-            height: config.height.max(1),
+            width: width.max(1),
+            height: height.max(1),
             depth_or_array_layers: 1,
         };
 
@@ -0,0 +1,414 @@
+use crate::chunk_mesher::{Face, MeshingMode, CHUNK_SIZE};
+use crate::chunk_renderer::ChunkRenderer;
+use crate::chunk_storage::{BlockId, ChunkStorage};
+use crate::mesh_worker::{MeshJob, MeshResult, MeshWorkerPool};
+use crate::render_backend::SceneObject;
+use cgmath::Vector3;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+/// Coordonnées entières d'un chunk (en chunks, pas en blocs)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ChunkPos {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+impl ChunkPos {
+    pub fn new(x: i32, y: i32, z: i32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Position du bloc (0, 0, 0) du chunk dans le monde
+    pub fn world_offset(&self) -> Vector3<f32> {
+        let size = CHUNK_SIZE as f32;
+        Vector3::new(self.x as f32 * size, self.y as f32 * size, self.z as f32 * size)
+    }
+
+    /// Chunk adjacent du côté de `face`
+    pub fn neighbour(&self, face: Face) -> ChunkPos {
+        let [dx, dy, dz] = face.offset();
+        ChunkPos::new(self.x + dx, self.y + dy, self.z + dz)
+    }
+
+    /// Chunk contenant un bloc du monde, et coordonnées locales du bloc dans ce chunk
+    pub fn from_block(x: i32, y: i32, z: i32) -> (ChunkPos, [usize; 3]) {
+        let size = CHUNK_SIZE as i32;
+        let pos = ChunkPos::new(x.div_euclid(size), y.div_euclid(size), z.div_euclid(size));
+        let local = [x, y, z].map(|c| c.rem_euclid(size) as usize);
+        (pos, local)
+    }
+}
+
+/// Modification d'un bloc, en coordonnées de blocs du monde
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockEdit {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+    pub block_id: BlockId,
+}
+
+/// Faces du chunk touchées par un bloc en bordure (voisins à régénérer)
+fn border_faces(local: [usize; 3]) -> impl Iterator<Item = Face> {
+    Face::ALL.into_iter().filter(move |face| {
+        let c = local[face.axis()];
+        if face.is_positive() { c == CHUNK_SIZE - 1 } else { c == 0 }
+    })
+}
+
+/// Nombre de meshes envoyés au GPU par frame si rien n'est configuré
+pub const DEFAULT_UPLOAD_BUDGET: usize = 4;
+
+/// Un chunk chargé : ses blocs et les objets GPU de son mesh
+pub struct Chunk {
+    /// Partagé avec les threads de meshing, copié à l'écriture s'ils le lisent encore
+    data: Arc<ChunkStorage>,
+    /// Incrémenté à chaque demande de meshing
+    generation: u64,
+    /// Version du chunk affichée par `objects`
+    meshed_generation: u64,
+    objects: Vec<SceneObject>,
+}
+
+/// Ensemble des chunks chargés, indexés par leurs coordonnées
+pub struct World {
+    chunks: HashMap<ChunkPos, Chunk>,
+    workers: MeshWorkerPool,
+    meshing_mode: MeshingMode,
+    /// Meshes terminés en attente d'envoi au GPU
+    ready: VecDeque<MeshResult>,
+    upload_budget: usize,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self {
+            chunks: HashMap::new(),
+            workers: MeshWorkerPool::new(MeshWorkerPool::default_thread_count()),
+            meshing_mode: MeshingMode::from_env(),
+            ready: VecDeque::new(),
+            upload_budget: upload_budget_from_env(),
+        }
+    }
+
+    /// Ajouter ou remplacer un chunk, générer son mesh et celui de ses voisins chargés
+    pub fn insert_chunk(&mut self, pos: ChunkPos, data: ChunkStorage) {
+        // Garder l'ancien mesh affiché jusqu'à ce que le nouveau soit prêt
+        match self.chunks.get_mut(&pos) {
+            Some(chunk) => chunk.data = Arc::new(data),
+            None => {
+                self.chunks.insert(
+                    pos,
+                    Chunk {
+                        data: Arc::new(data),
+                        generation: 0,
+                        meshed_generation: 0,
+                        objects: Vec::new(),
+                    },
+                );
+            }
+        }
+
+        self.schedule_remesh(pos);
+        self.schedule_neighbours(pos);
+    }
+
+    /// Décharger un chunk, renvoie false s'il n'était pas chargé
+    pub fn remove_chunk(&mut self, pos: ChunkPos) -> bool {
+        if self.chunks.remove(&pos).is_none() {
+            return false;
+        }
+
+        // Les faces des voisins contre ce chunk redeviennent visibles
+        self.schedule_neighbours(pos);
+        true
+    }
+
+    /// Modifier des blocs sur place et ne régénérer que les chunks touchés
+    pub fn set_blocks(&mut self, edits: &[BlockEdit]) {
+        let mut dirty = HashSet::new();
+
+        for edit in edits {
+            let (pos, local) = ChunkPos::from_block(edit.x, edit.y, edit.z);
+            let Some(chunk) = self.chunks.get_mut(&pos) else {
+                log::warn!("Ignoring block edit in unloaded chunk {:?}: {:?}", pos, edit);
+                continue;
+            };
+
+            let [x, y, z] = local;
+            if chunk.data.get(x, y, z) == edit.block_id {
+                continue;
+            }
+            Arc::make_mut(&mut chunk.data).set(x, y, z, edit.block_id);
+
+            dirty.insert(pos);
+            for face in border_faces(local) {
+                dirty.insert(pos.neighbour(face));
+            }
+        }
+
+        for pos in dirty {
+            self.schedule_remesh(pos);
+        }
+    }
+
+    /// Régénérer tous les chunks chargés (ex. après un changement de registre de blocs)
+    pub fn remesh_all(&mut self) {
+        let positions: Vec<ChunkPos> = self.chunks.keys().copied().collect();
+        for pos in positions {
+            self.schedule_remesh(pos);
+        }
+    }
+
+    /// Envoyer une copie du chunk et de ses voisins aux threads de meshing
+    fn schedule_remesh(&mut self, pos: ChunkPos) {
+        let mut neighbours: [Option<Arc<ChunkStorage>>; 6] = Default::default();
+        for face in Face::ALL {
+            neighbours[face.index()] = self
+                .chunks
+                .get(&pos.neighbour(face))
+                .map(|neighbour| Arc::clone(&neighbour.data));
+        }
+
+        let Some(chunk) = self.chunks.get_mut(&pos) else {
+            return;
+        };
+        chunk.generation += 1;
+
+        self.workers.submit(MeshJob {
+            pos,
+            generation: chunk.generation,
+            mode: self.meshing_mode,
+            center: Arc::clone(&chunk.data),
+            neighbours,
+        });
+    }
+
+    /// Régénérer les voisins chargés, dont les faces de bord dépendent de ce chunk
+    fn schedule_neighbours(&mut self, pos: ChunkPos) {
+        for face in Face::ALL {
+            self.schedule_remesh(pos.neighbour(face));
+        }
+    }
+
+    /// Le mesh correspond-il toujours à la dernière version du chunk ?
+    fn is_current(&self, result: &MeshResult) -> bool {
+        self.chunks
+            .get(&result.pos)
+            .is_some_and(|chunk| chunk.generation == result.generation)
+    }
+
+    /// Récupérer les meshes terminés par les threads, sans les envoyer au GPU
+    fn collect_finished(&mut self) {
+        let finished: Vec<MeshResult> = self.workers.try_recv().collect();
+        for result in finished {
+            if self.is_current(&result) {
+                self.ready.push_back(result);
+            }
+        }
+    }
+
+    /// Attendre le prochain mesh terminé, au plus `timeout`, et le garder s'il est à jour.
+    /// Renvoie false si aucun mesh n'est arrivé à temps.
+    fn wait_finished(&mut self, timeout: std::time::Duration) -> bool {
+        let Some(result) = self.workers.recv_timeout(timeout) else {
+            return false;
+        };
+        if self.is_current(&result) {
+            self.ready.push_back(result);
+        }
+        true
+    }
+
+    /// Envoyer au GPU le prochain mesh prêt. Renvoie `None` s'il n'y en a plus, sinon s'il
+    /// a été envoyé ; en cas d'échec, il repasse en fin de file pour ne pas bloquer les autres.
+    fn upload_next(
+        &mut self,
+        device: &wgpu::Device,
+        chunk_renderer: &mut ChunkRenderer,
+    ) -> anyhow::Result<Option<bool>> {
+        let Some(result) = self.ready.pop_front() else {
+            return Ok(None);
+        };
+        match self.upload(device, chunk_renderer, &result) {
+            Ok(uploaded) => Ok(Some(uploaded)),
+            Err(e) => {
+                self.ready.push_back(result);
+                Err(e)
+            }
+        }
+    }
+
+    /// Envoyer au GPU au plus `upload_budget` meshes terminés, renvoie le nombre envoyé
+    pub fn upload_ready_meshes(
+        &mut self,
+        device: &wgpu::Device,
+        chunk_renderer: &mut ChunkRenderer,
+    ) -> anyhow::Result<usize> {
+        self.collect_finished();
+
+        let mut uploaded = 0;
+        while uploaded < self.upload_budget {
+            match self.upload_next(device, chunk_renderer)? {
+                Some(true) => uploaded += 1,
+                Some(false) => {}
+                None => break,
+            }
+        }
+
+        Ok(uploaded)
+    }
+
+    /// Attendre les threads de meshing et envoyer au GPU le mesh à jour de chaque chunk,
+    /// sans limite par frame. Renvoie le nombre de meshes envoyés.
+    pub fn upload_all_meshes(
+        &mut self,
+        device: &wgpu::Device,
+        chunk_renderer: &mut ChunkRenderer,
+        timeout: std::time::Duration,
+    ) -> anyhow::Result<usize> {
+        let deadline = std::time::Instant::now() + timeout;
+        let mut uploaded = 0;
+        loop {
+            self.collect_finished();
+            while let Some(sent) = self.upload_next(device, chunk_renderer)? {
+                if sent {
+                    uploaded += 1;
+                }
+            }
+            if self.is_meshed() {
+                return Ok(uploaded);
+            }
+
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            anyhow::ensure!(!remaining.is_zero(), "Chunk meshing did not finish in {:?}", timeout);
+            self.wait_finished(remaining);
+        }
+    }
+
+    /// Remplacer les objets du chunk par ce mesh, renvoie false s'il est périmé
+    fn upload(
+        &mut self,
+        device: &wgpu::Device,
+        chunk_renderer: &mut ChunkRenderer,
+        result: &MeshResult,
+    ) -> anyhow::Result<bool> {
+        // Le chunk a pu changer ou être déchargé depuis la fin du meshing
+        if !self.is_current(result) {
+            return Ok(false);
+        }
+
+        let objects = chunk_renderer.upload_chunk_mesh(device, &result.mesh, result.pos.world_offset())?;
+        if let Some(chunk) = self.chunks.get_mut(&result.pos) {
+            chunk.objects = objects;
+            chunk.meshed_generation = result.generation;
+        }
+        Ok(true)
+    }
+
+    /// Tous les chunks affichent-ils leur dernière version ?
+    fn is_meshed(&self) -> bool {
+        self.chunks
+            .values()
+            .all(|chunk| chunk.meshed_generation == chunk.generation)
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Tous les objets à dessiner, chunk par chunk
+    pub fn objects(&self) -> impl Iterator<Item = &SceneObject> {
+        self.chunks.values().flat_map(|chunk| chunk.objects.iter())
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lire le budget d'envoi depuis `MINI_GAME_MESH_UPLOAD_BUDGET`
+fn upload_budget_from_env() -> usize {
+    std::env::var("MINI_GAME_MESH_UPLOAD_BUDGET")
+        .ok()
+        .and_then(|value| value.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_UPLOAD_BUDGET)
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_offset_scales_by_chunk_size() {
+        assert_eq!(ChunkPos::new(0, 0, 0).world_offset(), Vector3::new(0.0, 0.0, 0.0));
+        assert_eq!(ChunkPos::new(1, -2, 3).world_offset(), Vector3::new(32.0, -64.0, 96.0));
+    }
+
+    #[test]
+    fn from_block_splits_world_coordinates() {
+        assert_eq!(ChunkPos::from_block(0, 0, 0), (ChunkPos::new(0, 0, 0), [0, 0, 0]));
+        assert_eq!(ChunkPos::from_block(33, 31, 64), (ChunkPos::new(1, 0, 2), [1, 31, 0]));
+        assert_eq!(ChunkPos::from_block(-1, -32, -33), (ChunkPos::new(-1, -1, -2), [31, 0, 31]));
+    }
+
+    #[test]
+    fn border_blocks_touch_neighbours() {
+        assert_eq!(border_faces([5, 5, 5]).count(), 0);
+        assert_eq!(border_faces([0, 5, 5]).collect::<Vec<_>>(), vec![Face::NegX]);
+        assert_eq!(
+            border_faces([CHUNK_SIZE - 1, 0, 5]).collect::<Vec<_>>(),
+            vec![Face::PosX, Face::NegY]
+        );
+    }
+
+    #[test]
+    fn neighbour_follows_face_direction() {
+        let pos = ChunkPos::new(4, 5, 6);
+        assert_eq!(pos.neighbour(Face::PosX), ChunkPos::new(5, 5, 6));
+        assert_eq!(pos.neighbour(Face::NegY), ChunkPos::new(4, 4, 6));
+        assert_eq!(pos.neighbour(Face::NegZ), ChunkPos::new(4, 5, 5));
+    }
+
+    /// Attendre les `count` prochains meshes des threads, dans l'ordre où ils finissent
+    fn wait_for_meshes(world: &mut World, count: usize) {
+        for _ in 0..count {
+            assert!(
+                world.wait_finished(std::time::Duration::from_secs(10)),
+                "Mesh workers did not finish"
+            );
+        }
+    }
+
+    #[test]
+    fn stale_meshes_are_discarded() {
+        let mut world = World::new();
+        let pos = ChunkPos::default();
+        world.insert_chunk(pos, ChunkStorage::empty());
+        world.set_blocks(&[BlockEdit { x: 1, y: 1, z: 1, block_id: 2 }]);
+
+        // Deux demandes de meshing, seule la dernière version est gardée
+        wait_for_meshes(&mut world, 2);
+
+        assert_eq!(world.ready.len(), 1);
+        assert_eq!(world.ready[0].generation, 2);
+        assert_eq!(world.ready[0].mesh.quad_count(), 6);
+    }
+
+    #[test]
+    fn unloaded_chunks_drop_their_pending_meshes() {
+        let mut world = World::new();
+        let pos = ChunkPos::new(3, 0, 0);
+        world.insert_chunk(pos, ChunkStorage::Uniform(1));
+        assert!(world.remove_chunk(pos));
+
+        // Le seul job envoyé (aucun voisin n'est chargé) se termine, puis est ignoré
+        wait_for_meshes(&mut world, 1);
+        assert!(world.ready.is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+/**
+ * Format du tableau renvoyé par {@link Teste#pollInputEvents()} : {@link #STRIDE} doubles
+ * par événement, (type, a, b).
+ */
+public final class InputEvents {
+    public static final int STRIDE = 3;
+
+    /** a = code {@code java.awt.event.KeyEvent.VK_*} (0 si inconnu), b = 1 si pressée */
+    public static final int KEY = 0;
+    /** a, b = déplacement brut de la souris (dx, dy) */
+    public static final int MOUSE_MOTION = 1;
+    /** a, b = défilement (dx, dy) en pixels, une ligne comptant pour 100 pixels */
+    public static final int SCROLL = 2;
+    /** a = 1 si la fenêtre a le focus */
+    public static final int FOCUS = 3;
+
+    private InputEvents() {
+    }
+}
@@ -0,0 +1,8 @@
+/**
+ * Erreur levée par le moteur de rendu natif. La chaîne de causes reprend celle de l'erreur Rust.
+ */
+public class RendererException extends RuntimeException {
+    public RendererException(String message, Throwable cause) {
+        super(message, cause);
+    }
+}
@@ -0,0 +1,7 @@
+/**
+ * Prévenu quand la boucle de rendu native se termine et a libéré ses ressources GPU.
+ */
+public interface RendererListener {
+    /** @param error null pour un arrêt normal, sinon l'erreur qui a arrêté le rendu */
+    void onRendererStopped(RendererException error);
+}
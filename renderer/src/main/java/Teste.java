@@ -1,7 +1,99 @@
+/**
+ * Points d'entrée natifs du moteur de rendu. Toute erreur côté Rust (y compris une panique)
+ * est levée sous forme de {@link RendererException}.
+ */
 public class Teste {
-    public static native void updateValue(double x, double y, double z);
+    /** Caméra déplacée au clavier et à la souris (mode par défaut). */
+    public static final int CAMERA_FREE_FLY = 0;
+    /** Caméra placée uniquement par {@link #setCamera}, les entrées ne la bougent plus. */
+    public static final int CAMERA_DRIVEN = 1;
+
+    /**
+     * Prépare un lancement de {@link #render()} : le listener (peut être null) est appelé
+     * sur le thread de rendu quand la boucle se termine. Interdit pendant le rendu.
+     */
+    public static native void init(RendererListener listener);
+    /**
+     * Bloque jusqu'à la fin de la boucle de rendu ; peut être relancé sur le même thread.
+     * Si une boucle tourne déjà, lève une {@link RendererException} sans appeler le listener.
+     */
     public static native void render();
+    /** Demande l'arrêt de la boucle (GameState.STOP / EXIT), sans attendre sa fin. */
+    public static native void requestShutdown();
+    public static native boolean isRunning();
+    /**
+     * Rend sans fenêtre (1200×800) les chunks, entités et caméra envoyés depuis le dernier
+     * rendu, et écrit l'image en PNG. Interdit pendant le rendu.
+     */
+    public static native void renderToPng(String path);
+    /**
+     * Capture la prochaine frame de la fenêtre (comme F2) dans un PNG horodaté de
+     * {@code directory} ({@code null} : {@code MINI_GAME_SCREENSHOT_DIR} ou {@code screenshots}).
+     * Renvoie le chemin du fichier, écrit en arrière-plan. Lève une {@link RendererException}
+     * si la boucle de rendu ne tourne pas ou est encore en train de démarrer.
+     */
+    public static native String takeScreenshot(String directory);
+    /**
+     * Entrées du joueur depuis le dernier appel, par triplets (type, a, b) ;
+     * voir {@link InputEvents} pour les types. Au plus 1024 événements sont gardés.
+     */
+    public static native double[] pollInputEvents();
     public static native void updateChunk(float[] chunkData);
+    public static native void updateChunkAt(int chunkX, int chunkY, int chunkZ, float[] chunkData);
+    /**
+     * Chunk sous forme de ByteBuffer direct de 32768 ids {@code short}, dans l'ordre
+     * natif ({@code ByteBuffer.allocateDirect(65536).order(ByteOrder.nativeOrder())}).
+     * Les ids vont de 0 (air) à 32767 ({@code Short.MAX_VALUE}) : un {@code short} négatif
+     * est refusé, le bit de signe étant réservé.
+     * Lève une {@link RendererException} si la taille ou un id est invalide, ou si un id
+     * manque au registre de blocs actif (à envoyer avant les chunks qui l'utilisent).
+     */
+    public static native void updateChunkBuffer(int chunkX, int chunkY, int chunkZ, java.nio.ByteBuffer blockIds);
+    public static native void unloadChunk(int chunkX, int chunkY, int chunkZ);
+    public static native void setCameraMode(int mode);
+    /**
+     * Place la caméra dans n'importe quel mode. Angles en degrés : lacet -90 regarde vers -Z,
+     * tangage positif vers le haut (borné à ±90).
+     */
+    public static native void setCamera(double x, double y, double z, double yaw, double pitch);
+    /** Champ de vision vertical en degrés, dans ]0, 180[ (45 par défaut). */
+    public static native void setCameraFov(double fov);
+    /**
+     * État complet des entités à dessiner ; celles absentes de l'appel sont retirées.
+     * À appeler une fois par tick : le rendu interpole entre les deux derniers appels,
+     * avec donc un tick de retard.
+     * Pour l'entité i : positions[3i..3i+2] = (x, y, z), rotations[4i..4i+3] = quaternion
+     * (x, y, z, w) comme {@code org.joml.Quaterniond}, modelIds[i] = 0 (player) ou 1 (rocket).
+     * Un fichier {@code player.gltf}/{@code .glb} (ou {@code rocket}) du dossier
+     * {@code MINI_GAME_MODEL_DIR} remplace le modèle embarqué, avec ses textures externes.
+     */
+    public static native void syncEntities(int[] ids, double[] positions, double[] rotations, int[] modelIds);
+    /**
+     * Joue en boucle l'animation glTF {@code clip} du modèle de l'entité, à partir de
+     * {@code time} secondes et à la vitesse {@code speed} (1 = normale, 0 = figée).
+     * {@code clip} nul remet l'entité dans sa pose de repos ; un nom inconnu est signalé
+     * dans les logs et l'entité reste au repos.
+     */
+    public static native void setEntityAnimation(int entityId, String clip, double time, double speed);
+    /**
+     * Remplace les types de blocs (id, name, color, textures, flags) par un registre JSON au
+     * format de {@code blocks.json}, puis régénère les chunks. Lève une
+     * {@link RendererException} listant les IDs ou noms en double, les couleurs manquantes,
+     * les chemins de texture vides et les flags inconnus ({@code transparent},
+     * {@code non_solid}, {@code emissive}). Textures et flags ne sont pas encore dessinés.
+     */
+    public static native void setBlockRegistry(String json);
+    /** Comme {@link #setBlockRegistry}, en lisant le fichier {@code path}. */
+    public static native void loadBlockRegistry(String path);
+    /**
+     * ID du bloc {@code name} dans le dernier registre envoyé (sinon celui de
+     * {@code MINI_GAME_BLOCK_REGISTRY} ou le registre embarqué), pour {@link #setBlock}.
+     * Lève une {@link RendererException} si le nom est inconnu.
+     */
+    public static native int blockId(String name);
+    public static native void setBlock(int x, int y, int z, int blockId);
+    /** Blocs à modifier, par quadruplets (x, y, z, blockId) en coordonnées du monde. */
+    public static native void setBlocks(int[] packed);
 
     static {
         System.loadLibrary("rendering");
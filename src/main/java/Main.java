@@ -4,6 +4,12 @@ import world.Chunk;
 
 ExecutorService service = Executors.newSingleThreadExecutor();
 void main() {
+    // Le rendu s'arrête avec Teste.requestShutdown() (GameState.STOP / EXIT)
+    Teste.init(error -> {
+        if (error != null) {
+            error.printStackTrace();
+        }
+    });
     service.submit(Teste::render);
     service.shutdown();
 
@@ -35,5 +41,5 @@ void main() {
     Teste.updateChunk(chunk.block());
 
     // Mettre à jour la position de la caméra
-    Teste.updateValue(16.0, 10.0, 16.0);
+    Teste.setCamera(16.0, 10.0, 16.0, -90.0, -20.0);
 }
\ No newline at end of file